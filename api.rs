@@ -0,0 +1,83 @@
+use std::env;
+use std::process::Command;
+
+use crate::json::Value;
+use crate::network;
+use crate::repo::{GitRepository, HostKind};
+use crate::watchdog;
+
+/// GET `url` from `repo`'s provider's REST API with `curl`, or `None` if offline or the request
+/// fails.
+///
+/// Requests are authenticated with the provider's usual token variable when it's set, e.g.
+/// `GITHUB_TOKEN`, or for GitHub, the GitHub CLI's login. The header goes to curl on stdin, so
+/// the token never shows up in `ps`, and authenticated requests don't follow redirects, which
+/// could carry it to another host.
+pub fn get(repo: &GitRepository, url: &str) -> Option<Value> {
+    if network::is_offline() {
+        return None;
+    }
+
+    let header = token_header(repo);
+
+    let mut command = Command::new("curl");
+
+    command.args(["--silent", "--fail", "--header", "Accept: application/json"]);
+
+    if header.is_some() {
+        command.args(["--header", "@-"]);
+    } else {
+        command.arg("--location");
+    }
+
+    let output = watchdog::capture(command.arg(url), header.as_deref())
+        .ok()?
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Value::parse(&output.stdout)
+}
+
+/// The authorization header for `repo`'s API, from the first of its token variables that's set.
+///
+/// Any host GitHub isn't known to run is taken for GitHub Enterprise, so GitHub's tokens only go
+/// to github.com, or to the `gh` login for a host configured as GitHub.
+fn token_header(repo: &GitRepository) -> Option<String> {
+    let (vars, scheme): (&[&str], &str) = match repo.kind {
+        HostKind::GitHub if repo.host == "github.com" => {
+            (&["GITHUB_TOKEN", "GH_TOKEN"], "Authorization: Bearer ")
+        }
+        HostKind::GitHub if repo.provider_configured() => (&[], "Authorization: Bearer "),
+        HostKind::GitLab => (&["GITLAB_TOKEN"], "PRIVATE-TOKEN: "),
+        HostKind::Gitea => (&["GITEA_TOKEN"], "Authorization: token "),
+        _ => return None,
+    };
+
+    vars.iter()
+        .find_map(|var| env::var(var).ok().filter(|token| !token.is_empty()))
+        .or_else(|| {
+            (repo.kind == HostKind::GitHub)
+                .then(|| gh_token(&repo.host))
+                .flatten()
+        })
+        .map(|token| format!("{scheme}{token}"))
+}
+
+/// The token the GitHub CLI is logged in to `host` with, from `gh auth token`.
+fn gh_token(host: &str) -> Option<String> {
+    let output = watchdog::capture(
+        Command::new("gh").args(["auth", "token", "--hostname", host]),
+        None,
+    )
+    .ok()?
+    .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| output.stdout.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
@@ -0,0 +1,37 @@
+use std::process::Command;
+
+use crate::json::Value;
+use crate::watchdog;
+
+/// A Homebrew formula or cask's upstream homepage, falling back to its page on
+/// formulae.brew.sh when `brew` isn't installed or doesn't know the name.
+/// `formulae` always picks the formulae.brew.sh page, still asking `brew` whether it's a
+/// formula's or a cask's.
+pub fn url(name: &str, formulae: bool) -> String {
+    match info(name) {
+        Some((_, Some(homepage))) if !formulae => homepage,
+        Some((kind, _)) => format!("https://formulae.brew.sh/{kind}/{name}"),
+        None => format!("https://formulae.brew.sh/formula/{name}"),
+    }
+}
+
+/// Whether `name` is a formula or a cask, and its homepage, from `brew info`.
+fn info(name: &str) -> Option<(&'static str, Option<String>)> {
+    let output = watchdog::capture(Command::new("brew").args(["info", "--json=v2", name]), None)
+        .ok()?
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let info = Value::parse(&output.stdout)?;
+
+    [("formulae", "formula"), ("casks", "cask")]
+        .into_iter()
+        .find_map(|(key, kind)| {
+            let package = info.get(key)?.as_array()?.first()?;
+
+            Some((kind, package.get("homepage")?.as_str().map(str::to_string)))
+        })
+}
@@ -0,0 +1,73 @@
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+/// Slow lookups, remembered across runs as `key<TAB>value` lines in the XDG state directory.
+pub struct Cache {
+    path: Option<PathBuf>,
+}
+
+impl Cache {
+    pub fn new(name: &str) -> Self {
+        let dir = env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")));
+
+        Self {
+            path: dir.map(|dir| dir.join("magic-opener").join(name)),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.entries()
+            .into_iter()
+            .find_map(|(k, value)| (k == key).then_some(value))
+    }
+
+    pub fn set(&self, key: &str, value: &str) {
+        self.update(key, Some(value));
+    }
+
+    pub fn remove(&self, key: &str) {
+        self.update(key, None);
+    }
+
+    fn entries(&self) -> Vec<(String, String)> {
+        self.path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|contents| {
+                contents
+                    .lines()
+                    .filter_map(|line| line.split_once('\t'))
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Replace or drop an entry. A cache is best effort, so write failures are ignored.
+    fn update(&self, key: &str, value: Option<&str>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let mut entries = self.entries();
+
+        entries.retain(|(k, _)| k != key);
+        entries.extend(value.map(|value| (key.to_string(), value.to_string())));
+
+        let contents = entries.iter().fold(String::new(), |mut contents, (k, v)| {
+            let _ = writeln!(contents, "{k}\t{v}");
+            contents
+        });
+
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+
+        let _ = fs::write(path, contents);
+    }
+}
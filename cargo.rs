@@ -0,0 +1,30 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::json::Value;
+use crate::watchdog;
+
+/// The directory of the workspace member named `name`, as `cargo metadata` resolves the
+/// workspace manifest's `members` globs and `exclude`s.
+pub fn member_dir(dir: &Path, name: &str) -> Option<PathBuf> {
+    let output = watchdog::capture(
+        Command::new("cargo")
+            .args(["metadata", "--no-deps", "--offline", "--format-version=1"])
+            .current_dir(dir),
+        None,
+    )
+    .ok()?
+    .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Value::parse(&output.stdout)?
+        .get("packages")?
+        .as_array()?
+        .iter()
+        .find(|package| package.get("name").and_then(Value::as_str) == Some(name))
+        .and_then(|package| package.get("manifest_path")?.as_str())
+        .and_then(|manifest| Path::new(manifest).parent().map(Path::to_path_buf))
+}
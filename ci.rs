@@ -0,0 +1,153 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::api;
+use crate::json::Value;
+use crate::repo::{GitRepository, HostKind};
+
+/// Single-file CI definitions for the common providers, relative to the repository root.
+const CONFIG_FILES: [&str; 7] = [
+    ".gitlab-ci.yml",
+    "azure-pipelines.yml",
+    ".circleci/config.yml",
+    ".buildkite/pipeline.yml",
+    ".travis.yml",
+    "bitbucket-pipelines.yml",
+    "Jenkinsfile",
+];
+
+const WORKFLOWS: &str = ".github/workflows";
+
+/// The CI definitions present in the repository.
+///
+/// GitHub workflows are usually many small files, so the directory stands in for them unless
+/// `expand` asks for each file.
+pub fn config_files(repo: &GitRepository, expand: bool) -> Vec<String> {
+    let mut files = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(repo.root.join(WORKFLOWS)) {
+        if expand {
+            let mut workflows: Vec<String> = entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|extension| extension == "yml" || extension == "yaml")
+                })
+                .filter_map(|path| {
+                    path.file_name()
+                        .map(|name| format!("{WORKFLOWS}/{}", name.to_string_lossy()))
+                })
+                .collect();
+
+            workflows.sort();
+            files.extend(workflows);
+        } else {
+            files.push(WORKFLOWS.to_string());
+        }
+    }
+
+    files.extend(
+        CONFIG_FILES
+            .iter()
+            .filter(|file| repo.root.join(file).is_file())
+            .map(ToString::to_string),
+    );
+
+    files
+}
+
+/// A CI job's checkout, described by the provider's variables rather than git state: CI clones
+/// are shallow, on a detached HEAD, and fetch from a URL carrying a token.
+#[derive(Debug)]
+pub struct Environment {
+    pub workspace: PathBuf,
+    pub host: String,
+    /// `org/name`, or `group/subgroup/name` on GitLab.
+    pub path: String,
+    /// The branch being built, which is a pull request's head branch in a pull request build.
+    pub branch: Option<String>,
+}
+
+impl Environment {
+    /// The host, organization and name of the repository being built.
+    pub fn repository(&self) -> Option<(String, String, String)> {
+        let (org, name) = self.path.rsplit_once('/')?;
+
+        Some((self.host.clone(), org.to_string(), name.to_string()))
+    }
+}
+
+/// The environment of a GitHub Actions or GitLab CI job, if running in one.
+pub fn environment() -> Option<Environment> {
+    let var = |name| {
+        env::var(name)
+            .ok()
+            .filter(|value: &String| !value.is_empty())
+    };
+
+    let host = |url: String| {
+        url.split_once("://")
+            .map_or(url.as_str(), |(_, host)| host)
+            .trim_end_matches('/')
+            .to_string()
+    };
+
+    if var("GITHUB_ACTIONS").is_some() {
+        let branch = var("GITHUB_HEAD_REF").or_else(|| {
+            (var("GITHUB_REF_TYPE").as_deref() == Some("branch"))
+                .then(|| var("GITHUB_REF_NAME"))
+                .flatten()
+        });
+
+        return Some(Environment {
+            workspace: PathBuf::from(var("GITHUB_WORKSPACE")?),
+            host: host(var("GITHUB_SERVER_URL")?),
+            path: var("GITHUB_REPOSITORY")?,
+            branch,
+        });
+    }
+
+    if var("GITLAB_CI").is_some() {
+        return Some(Environment {
+            workspace: PathBuf::from(var("CI_PROJECT_DIR")?),
+            host: host(var("CI_SERVER_URL")?),
+            path: var("CI_PROJECT_PATH")?,
+            branch: var("CI_MERGE_REQUEST_SOURCE_BRANCH_NAME").or_else(|| var("CI_COMMIT_BRANCH")),
+        });
+    }
+
+    None
+}
+
+/// The CI run for commit `sha`, looked up with the provider's API, or else the commit's page of
+/// checks or pipelines.
+pub fn run_url(repo: &GitRepository, sha: &str) -> String {
+    let run = match repo.kind {
+        HostKind::GitHub | HostKind::Gitea => repo
+            .api_url(&format!("/actions/runs?head_sha={sha}"))
+            .and_then(|url| api::get(repo, &url))
+            .and_then(|runs| first_url(runs.get("workflow_runs")?, "html_url")),
+        HostKind::GitLab => repo
+            .api_url(&format!("/pipelines?sha={sha}"))
+            .and_then(|url| api::get(repo, &url))
+            .and_then(|pipelines| first_url(&pipelines, "web_url")),
+        _ => None,
+    };
+
+    run.unwrap_or_else(|| match repo.kind {
+        HostKind::GitHub => format!("{}/checks", repo.commit_url(sha)),
+        HostKind::GitLab => format!("{}/pipelines", repo.commit_url(sha)),
+        _ => repo.commit_url(sha),
+    })
+}
+
+/// The `key` URL of the first, i.e. latest, of a list of runs.
+fn first_url(runs: &Value, key: &str) -> Option<String> {
+    runs.as_array()?
+        .first()?
+        .get(key)?
+        .as_str()
+        .map(str::to_string)
+}
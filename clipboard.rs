@@ -0,0 +1,174 @@
+use std::env;
+#[cfg(target_os = "macos")]
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use clap::ValueEnum;
+
+#[cfg(target_os = "macos")]
+use crate::formats;
+use crate::messages;
+#[cfg(not(any(target_os = "macos", windows)))]
+use crate::opener;
+use crate::osc52;
+use crate::repo::Error;
+
+/// Whether `--copy` leaves it at that, or opens the targets too.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CopyMode {
+    Only,
+    Open,
+}
+
+/// Put `text` on the system clipboard, with the first of the platform's tools that works. On
+/// macOS, `links`, as `(title, url)` pairs, go alongside as HTML and RTF, so that pasting into
+/// Slack, Notes or Mail makes titled links.
+///
+/// Over SSH those would fill the server's clipboard, so the terminal's takes it through OSC 52.
+pub fn copy(text: &str, links: &[(String, String)]) -> Result<(), Error> {
+    if env::var_os("SSH_TTY").is_some() {
+        return osc52::copy(text).map_err(|err| Error::Io(PathBuf::from("/dev/tty"), err));
+    }
+
+    #[cfg(target_os = "macos")]
+    if !links.is_empty() && copy_rich(text, links) {
+        return Ok(());
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = links;
+
+    for mut command in commands() {
+        let Ok(mut child) = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+
+        // Dropping stdin closes it, so the tool knows the text is complete.
+        let written = child
+            .stdin
+            .take()
+            .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+
+        if child.wait().is_ok_and(|status| status.success()) && written {
+            return Ok(());
+        }
+    }
+
+    Err(Error::NotFound(messages::text("no-clipboard", &[])))
+}
+
+/// Fill the pasteboard's plain text, HTML and RTF flavors at once, which `pbcopy` can't, through
+/// JavaScript for Automation's bridge to `NSPasteboard`.
+#[cfg(target_os = "macos")]
+const PASTEBOARD_SCRIPT: &str = r"
+ObjC.import('AppKit');
+
+function run([text, html, rtf]) {
+    const pasteboard = $.NSPasteboard.generalPasteboard;
+
+    pasteboard.clearContents;
+    pasteboard.setStringForType($(html), $.NSPasteboardTypeHTML);
+    pasteboard.setStringForType($(rtf), $.NSPasteboardTypeRTF);
+    pasteboard.setStringForType($(text), $.NSPasteboardTypeString);
+}
+";
+
+/// Copy `text` with `links` as HTML and RTF, returning false to fall back to `pbcopy`.
+#[cfg(target_os = "macos")]
+fn copy_rich(text: &str, links: &[(String, String)]) -> bool {
+    let html = links
+        .iter()
+        .map(|(title, url)| {
+            format!(
+                "<a href=\"{}\">{}</a>",
+                formats::escape(url),
+                formats::escape(title)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("<br>");
+
+    let fields = links
+        .iter()
+        .map(|(title, url)| {
+            format!(
+                "{{\\field{{\\*\\fldinst{{HYPERLINK \"{}\"}}}}{{\\fldrslt{{{}}}}}}}",
+                rtf_escape(url),
+                rtf_escape(title)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\\line ");
+
+    Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", PASTEBOARD_SCRIPT, text, &html])
+        .arg(format!("{{\\rtf1\\ansi {fields}}}"))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// `text` for RTF, which reserves backslashes and braces, and wants anything but ASCII as
+/// `\uN?` escapes of UTF-16 code units.
+#[cfg(target_os = "macos")]
+fn rtf_escape(text: &str) -> String {
+    let mut escaped = String::new();
+
+    for c in text.chars() {
+        match c {
+            '\\' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_ascii() => escaped.push(c),
+            c => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    let _ = write!(escaped, "\\u{}?", i16::from_ne_bytes(unit.to_ne_bytes()));
+                }
+            }
+        }
+    }
+
+    escaped
+}
+
+#[cfg(target_os = "macos")]
+fn commands() -> Vec<Command> {
+    vec![Command::new("pbcopy")]
+}
+
+#[cfg(windows)]
+fn commands() -> Vec<Command> {
+    vec![Command::new("clip")]
+}
+
+/// Windows' `clip.exe` under WSL, otherwise Wayland's `wl-copy`, then X11's `xclip` or `xsel`.
+#[cfg(not(any(target_os = "macos", windows)))]
+fn commands() -> Vec<Command> {
+    if opener::is_wsl() {
+        return vec![Command::new("clip.exe")];
+    }
+
+    let mut commands = Vec::new();
+
+    if env::var_os("WAYLAND_DISPLAY").is_some() {
+        commands.push(Command::new("wl-copy"));
+    }
+
+    let mut xclip = Command::new("xclip");
+    xclip.args(["-selection", "clipboard"]);
+
+    let mut xsel = Command::new("xsel");
+    xsel.args(["--clipboard", "--input"]);
+
+    commands.extend([xclip, xsel]);
+    commands
+}
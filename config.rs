@@ -0,0 +1,33 @@
+use std::path::Path;
+
+use crate::git;
+
+/// Read a setting from the `[magic-opener]` section of git config, so defaults can live in
+/// `~/.gitconfig` and be overridden per repository.
+pub fn get(dir: &Path, key: &str) -> Option<String> {
+    git::output(dir, &["config", "--get", &format!("magic-opener.{key}")])
+}
+
+/// Read a boolean setting, with git's notion of truthy values.
+pub fn get_bool(dir: &Path, key: &str) -> bool {
+    git::output(
+        dir,
+        &[
+            "config",
+            "--type=bool",
+            "--get",
+            &format!("magic-opener.{key}"),
+        ],
+    )
+    .is_some_and(|value| value == "true")
+}
+
+/// Read every value of a multi-valued setting, in config order.
+pub fn get_all(dir: &Path, key: &str) -> Vec<String> {
+    git::output(
+        dir,
+        &["config", "--get-all", &format!("magic-opener.{key}")],
+    )
+    .map(|values| values.lines().map(str::to_string).collect())
+    .unwrap_or_default()
+}
@@ -0,0 +1,85 @@
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::process::Command;
+
+use crate::config;
+use crate::messages;
+use crate::resolve::is_url;
+
+/// Whether opening `targets` may go ahead.
+///
+/// Nothing is asked unless a `confirm-*` or `relay-hosts` setting finds the batch surprising.
+/// Then the question goes to stderr, and without a terminal to answer on, the answer is no.
+pub fn confirmed(targets: &[String], relay: bool) -> bool {
+    let reasons = reasons(targets, relay);
+
+    if reasons.is_empty() {
+        return true;
+    }
+
+    let mut stderr = io::stderr().lock();
+
+    for reason in &reasons {
+        let _ = writeln!(stderr, "{reason}");
+    }
+
+    if !io::stdin().is_terminal() {
+        let _ = writeln!(stderr, "{}", messages::text("confirm-needs-yes", &[]));
+        return false;
+    }
+
+    let _ = write!(stderr, "{} [y/N] ", messages::text("confirm-prompt", &[]));
+    let _ = stderr.flush();
+
+    let mut line = String::new();
+
+    io::stdin().read_line(&mut line).is_ok()
+        && matches!(line.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+fn reasons(targets: &[String], relay: bool) -> Vec<String> {
+    let dir = Path::new(".");
+    let mut reasons = Vec::new();
+
+    if let Some(limit) = config::get(dir, "confirm-over").and_then(|limit| limit.parse().ok()) {
+        if targets.len() > limit {
+            reasons.push(messages::text("confirm-many", &[("count", &targets.len())]));
+        }
+    }
+
+    if config::get_bool(dir, "confirm-schemes") {
+        reasons.extend(
+            targets
+                .iter()
+                .filter(|target| is_url(target) && !target.starts_with("https://"))
+                .map(|target| messages::text("confirm-scheme", &[("target", target)])),
+        );
+    }
+
+    let trusted = config::get_all(dir, "relay-hosts");
+
+    if relay && !trusted.is_empty() {
+        let host = hostname().unwrap_or_default();
+
+        if !trusted.contains(&host) {
+            reasons.push(messages::text("confirm-relay", &[("host", &host)]));
+        }
+    }
+
+    reasons
+}
+
+fn hostname() -> Option<String> {
+    env::var("HOSTNAME")
+        .ok()
+        .filter(|host| !host.is_empty())
+        .or_else(|| {
+            let output = Command::new("hostname").output().ok()?;
+
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        })
+}
@@ -0,0 +1,271 @@
+use std::fs;
+use std::io::{self, Read};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::config;
+use crate::messages;
+use crate::opener;
+use crate::paths::{expand_tilde, PathMapper};
+use crate::repo::Error;
+use crate::resolve::is_url;
+use crate::{LOCALHOST, PORT};
+
+/// Schemes relayed URLs may use: the web, mail, and the editors `--editor` links to.
+/// `magic-opener.daemon-schemes` allows more.
+const SCHEMES: [&str; 5] = ["https", "http", "mailto", "vscode", "jetbrains"];
+
+/// Characters no relayed target needs, which shells and `cmd` would read as syntax.
+const METACHARACTERS: [char; 7] = ['"', '%', '^', '&', '|', '<', '>'];
+
+/// Extensions of the documents the daemon opens: text, images, media, office files and web
+/// pages, whose default applications show them rather than run them.
+const DOCUMENTS: [&str; 46] = [
+    "avi", "bmp", "csv", "diff", "doc", "docx", "epub", "flac", "gif", "heic", "htm", "html",
+    "ico", "jpeg", "jpg", "json", "key", "log", "m4a", "md", "mkv", "mov", "mp3", "mp4", "numbers",
+    "odp", "ods", "odt", "ogg", "pages", "patch", "pdf", "png", "ppt", "pptx", "rst", "rtf", "svg",
+    "tif", "tiff", "toml", "tsv", "txt", "wav", "webp", "xlsx",
+];
+
+/// The most a client may send at once. A batch of links is far smaller.
+const MAX_PAYLOAD: usize = 64 * 1024;
+
+/// How long a client gets to send its batch, so a stuck connection can't hold up the next.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Listen for the targets `open` relays from SSH sessions through the forwarded port, and open
+/// each one that passes [`check`] here, until killed.
+///
+/// Only loopback is bound, so targets come from this machine and the tunnels it forwards.
+pub fn serve(dir: &Path) -> Result<(), Error> {
+    let listener = TcpListener::bind((LOCALHOST, PORT))
+        .map_err(|err| Error::Io(PathBuf::from(format!("{LOCALHOST}:{PORT}")), err))?;
+
+    let mut schemes: Vec<String> = SCHEMES.iter().map(|scheme| (*scheme).to_string()).collect();
+    schemes.extend(config::get_all(dir, "daemon-schemes"));
+
+    // The same `mount` rules the server maps its paths with, from this end.
+    let mounts = PathMapper::from_config(dir, &expand_tilde("~"));
+
+    eprintln!("{}", messages::text("daemon-listening", &[("port", &PORT)]));
+
+    for stream in listener.incoming().filter_map(Result::ok) {
+        let payload = match receive(&stream) {
+            Ok(payload) => payload,
+            Err(err) => {
+                eprintln!(
+                    "{}",
+                    messages::text("daemon-bad-payload", &[("error", &err)])
+                );
+                continue;
+            }
+        };
+
+        for target in payload
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            match check(target, &schemes, &mounts) {
+                Ok(target) => {
+                    if let Err(err) = opener::open(&target) {
+                        eprintln!(
+                            "{}",
+                            messages::text(
+                                "daemon-failed",
+                                &[("target", &target), ("error", &err)]
+                            )
+                        );
+                    }
+                }
+                Err(reason) => eprintln!("{reason}"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A client's batch of targets, a line each, in full or not at all.
+fn receive(stream: &TcpStream) -> io::Result<String> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+    let mut payload = Vec::new();
+
+    stream
+        .take(MAX_PAYLOAD as u64 + 1)
+        .read_to_end(&mut payload)?;
+
+    if payload.len() > MAX_PAYLOAD {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            messages::text("daemon-too-large", &[("limit", &MAX_PAYLOAD)]),
+        ));
+    }
+
+    String::from_utf8(payload).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// `target` ready to open if it's safe to: a URL with one of `schemes`, or an existing directory
+/// or document under one of the `mounts`, the only paths a server can have mapped to this end. Anything else is refused with the reason, not least arguments the launcher
+/// would take for flags.
+fn check(target: &str, schemes: &[String], mounts: &PathMapper) -> Result<String, String> {
+    let refuse = |id| Err(messages::text(id, &[("target", &target)]));
+
+    if target.starts_with('-') || target.contains('"') || target.chars().any(char::is_control) {
+        return refuse("daemon-refused");
+    }
+
+    if is_url(target) {
+        let scheme = target.split(':').next().unwrap_or_default().to_lowercase();
+
+        return if schemes.contains(&scheme) {
+            Ok(target.to_string())
+        } else {
+            refuse("daemon-refused-scheme")
+        };
+    }
+
+    // Resolve `..` and symlinks first, so only where the path really leads is checked.
+    let Ok(path) = fs::canonicalize(expand_tilde(target)) else {
+        return refuse("daemon-refused-path");
+    };
+
+    let display = path.to_string_lossy().to_string();
+
+    if target.contains(METACHARACTERS) || mounts.canonicalized().unmap(&display) == display {
+        return refuse("daemon-refused-path");
+    }
+
+    if is_runnable(&path) {
+        return refuse("daemon-refused-runnable");
+    }
+
+    Ok(display)
+}
+
+/// Whether opening `path` might run something rather than show it: anything in an app or other
+/// bundle, and any file but a document that isn't executable.
+fn is_runnable(path: &Path) -> bool {
+    if path
+        .ancestors()
+        .any(|dir| dir.join("Contents").join("Info.plist").is_file())
+    {
+        return true;
+    }
+
+    let document = path.extension().is_some_and(|extension| {
+        DOCUMENTS.contains(&extension.to_string_lossy().to_lowercase().as_str())
+    });
+
+    path.is_file() && (!document || is_executable(path))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::metadata(path).is_ok_and(|metadata| metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::check;
+    use crate::paths::PathMapper;
+
+    /// A fresh directory for `name` with a mount in it and a file next to it, outside.
+    fn fixture(name: &str) -> (PathBuf, PathMapper) {
+        let dir =
+            env::temp_dir().join(format!("magic-opener-daemon-{name}-{}", std::process::id()));
+        let mount = dir.join("mount");
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(mount.join("App.app").join("Contents")).expect("fixture");
+        fs::write(
+            mount.join("App.app").join("Contents").join("Info.plist"),
+            "",
+        )
+        .expect("fixture");
+
+        for file in ["notes.txt", "Report.PDF", "x.fileloc", "run.py", "README"] {
+            fs::write(mount.join(file), "").expect("fixture");
+        }
+
+        fs::write(dir.join("outside.txt"), "").expect("fixture");
+
+        let mounts = PathMapper::new(vec![(
+            "/bits".to_string(),
+            mount.to_string_lossy().to_string(),
+        )]);
+
+        (mount, mounts)
+    }
+
+    fn schemes() -> Vec<String> {
+        vec!["https".to_string()]
+    }
+
+    #[test]
+    fn opens_documents_and_directories_under_a_mount() {
+        let (mount, mounts) = fixture("documents");
+
+        for path in [
+            mount.join("notes.txt"),
+            mount.join("Report.PDF"),
+            mount.clone(),
+        ] {
+            let target = path.to_string_lossy();
+
+            assert!(check(&target, &schemes(), &mounts).is_ok(), "{target}");
+        }
+    }
+
+    #[test]
+    fn refuses_paths_leading_out_of_the_mount() {
+        let (mount, mounts) = fixture("escape");
+
+        for target in [
+            format!("{}/../outside.txt", mount.display()),
+            format!("{}/missing.txt", mount.display()),
+            "/etc/hosts".to_string(),
+        ] {
+            assert!(check(&target, &schemes(), &mounts).is_err(), "{target}");
+        }
+    }
+
+    #[test]
+    fn refuses_what_would_run() {
+        let (mount, mounts) = fixture("runnable");
+
+        for path in [
+            mount.join("x.fileloc"),
+            mount.join("run.py"),
+            mount.join("README"),
+            mount.join("App.app"),
+            mount.join("App.app").join("Contents").join("Info.plist"),
+        ] {
+            let target = path.to_string_lossy();
+
+            assert!(check(&target, &schemes(), &mounts).is_err(), "{target}");
+        }
+    }
+
+    #[test]
+    fn checks_urls_by_scheme() {
+        let mounts = PathMapper::new(Vec::new());
+
+        assert!(check("https://example.com", &schemes(), &mounts).is_ok());
+        assert!(check("file:///etc/passwd", &schemes(), &mounts).is_err());
+        assert!(check("-a Terminal", &schemes(), &mounts).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use clap::ValueEnum;
+
+use crate::git;
+use crate::location::Location;
+use crate::repo::{encode, encode_path};
+use crate::LOCALHOST;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Editor {
+    #[value(name = "vscode", alias = "code")]
+    VSCode,
+    #[value(name = "idea", alias = "intellij")]
+    Idea,
+    #[value(name = "clion")]
+    CLion,
+    #[value(name = "goland")]
+    GoLand,
+    #[value(name = "pycharm")]
+    PyCharm,
+    #[value(name = "rustrover")]
+    RustRover,
+    #[value(name = "webstorm")]
+    WebStorm,
+}
+
+impl Editor {
+    /// Build a deep link that opens the location in the editor.
+    ///
+    /// Over SSH the link points the editor's remote extension back at this host.
+    pub fn url(self, location: &Location, remote: bool) -> String {
+        let position = match (location.line, location.column) {
+            (Some(line), Some(column)) => format!(":{line}:{column}"),
+            (Some(line), None) => format!(":{line}"),
+            _ => String::new(),
+        };
+
+        if let Some(tag) = self.tool_tag() {
+            // JetBrains IDEs resolve paths relative to an open project, identified by name.
+            let root = project_root(&location.path);
+
+            let project = root
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let path = location.path.strip_prefix(&root).unwrap_or(&location.path);
+
+            return format!(
+                "jetbrains://{tag}/navigate/reference?project={}&path={}{position}",
+                encode(&project),
+                encode(&path.to_string_lossy())
+            );
+        }
+
+        if remote {
+            format!(
+                "vscode://vscode-remote/ssh-remote+{}{}{position}",
+                hostname(),
+                encode_path(&location.path.to_string_lossy())
+            )
+        } else {
+            format!(
+                "vscode://file{}{position}",
+                encode_path(&location.path.to_string_lossy())
+            )
+        }
+    }
+
+    /// The Toolbox tag each IDE registers for `jetbrains://` links, or none for VS Code.
+    fn tool_tag(self) -> Option<&'static str> {
+        match self {
+            Self::VSCode => None,
+            Self::Idea => Some("idea"),
+            Self::CLion => Some("clion"),
+            Self::GoLand => Some("goland"),
+            Self::PyCharm => Some("pycharm"),
+            Self::RustRover => Some("rustrover"),
+            Self::WebStorm => Some("web-storm"),
+        }
+    }
+}
+
+/// The enclosing Git work tree, or the file's directory outside of one.
+fn project_root(path: &Path) -> PathBuf {
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(path)
+    };
+
+    git::output(dir, &["rev-parse", "--show-toplevel"])
+        .map_or_else(|| dir.to_path_buf(), PathBuf::from)
+}
+
+fn hostname() -> String {
+    Command::new("hostname")
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| LOCALHOST.to_string())
+}
@@ -0,0 +1,43 @@
+use std::path::Path;
+
+use crate::config;
+
+/// The built-in `--format` templates, by name. `magic-opener.format.<name>` adds more, or
+/// replaces these.
+const FORMATS: [(&str, &str); 3] = [
+    ("plain", "{url}"),
+    ("md", "[{title}]({url})"),
+    ("html", "<a href=\"{url}\">{title}</a>"),
+];
+
+/// The template `format` names, configured or built in, or `format` itself when it's a template
+/// of its own, e.g. `{org}/{name}: {url}`.
+pub fn template(dir: &Path, format: &str) -> Option<String> {
+    config::get(dir, &format!("format.{format}"))
+        .or_else(|| {
+            FORMATS
+                .iter()
+                .find_map(|(name, template)| (*name == format).then(|| (*template).to_string()))
+        })
+        .or_else(|| format.contains('{').then(|| format.to_string()))
+}
+
+/// `template` with each `{name}` filled in from `vars`, escaped when the template is HTML.
+pub fn render(template: &str, vars: &[(String, String)]) -> String {
+    let html = template.trim_start().starts_with('<');
+
+    vars.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            let value = if html { escape(value) } else { value.clone() };
+
+            text.replace(&format!("{{{name}}}"), &value)
+        })
+}
+
+/// `text` safe to put in HTML, as an element's content or an attribute's value.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
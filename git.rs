@@ -0,0 +1,57 @@
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+
+use crate::network;
+use crate::repo::Error;
+use crate::watchdog;
+
+/// Subcommands that talk to a remote, and so are refused when offline.
+const NETWORK_COMMANDS: [&str; 5] = ["clone", "fetch", "ls-remote", "pull", "push"];
+
+/// Run `git` in `dir`, returning its stdout without the trailing newline.
+pub fn run(dir: &Path, args: &[&str]) -> Result<String, Error> {
+    let command = args.join(" ");
+
+    let mut git = Command::new("git");
+    git.arg("-C").arg(dir).args(args);
+
+    // The watchdog is for stale mounts; a fetch or clone is just slow.
+    let output = if args
+        .first()
+        .is_some_and(|command| NETWORK_COMMANDS.contains(command))
+    {
+        network::check(&format!("git {command}"))?;
+
+        watchdog::capture_unwatched(&mut git, None)
+            .map_err(|err| Error::Git(command.clone(), err.to_string()))?
+    } else {
+        watchdog::capture(&mut git, None)
+            .map_err(|err| Error::Git(command.clone(), err.to_string()))?
+            .map_err(|timeout| Error::Timeout(command.clone(), timeout))?
+    };
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(Error::Git(command, output.stderr))
+    }
+}
+
+/// Like [`run`], for lookups where failure just means "not found".
+pub fn output(dir: &Path, args: &[&str]) -> Option<String> {
+    run(dir, args).ok()
+}
+
+/// Run independent `git` commands in `dir` concurrently, returning results in the same order.
+pub fn run_all<const N: usize>(dir: &Path, commands: [&[&str]; N]) -> [Result<String, Error>; N] {
+    thread::scope(|scope| {
+        commands
+            .map(|args| (args, scope.spawn(move || run(dir, args))))
+            .map(|(args, handle)| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err(Error::Git(args.join(" "), "panicked".to_string())))
+            })
+    })
+}
@@ -0,0 +1,78 @@
+use std::env;
+use std::path::Path;
+
+use clap::Parser;
+
+use crate::git;
+use crate::messages;
+use crate::repo::{Error, GitRepository};
+use crate::REMOTE_NAME;
+
+/// The arguments of the `git-open` script, for when this binary is installed as `git-open`.
+#[derive(Parser, Debug)]
+#[clap(
+    name = "git-open",
+    about = "Open the repository's web page, as `git open` would."
+)]
+pub struct GitOpen {
+    #[clap(short, long, help = "Print the URL to stdout instead of opening it.")]
+    pub print: bool,
+
+    #[clap(short, long, help = "Open the current commit.")]
+    commit: bool,
+
+    #[clap(help = "Remote to open, or a branch if no such remote exists.")]
+    remote: Option<String>,
+
+    #[clap(help = "Branch to open. Defaults to the current branch.")]
+    branch: Option<String>,
+}
+
+/// Whether git invoked us as the `git open` extension, via a `git-open` link to this binary.
+pub fn invoked() -> bool {
+    env::args_os()
+        .next()
+        .and_then(|arg0| Path::new(&arg0).file_name().map(|name| name == "git-open"))
+        .unwrap_or_default()
+}
+
+impl GitOpen {
+    pub fn url(&self, cwd: &Path) -> Result<String, Error> {
+        let remotes = git::run(cwd, &["remote"])?;
+
+        // `git open feature` means the branch when there is no remote by that name.
+        let (remote, branch) = match (&self.remote, &self.branch) {
+            (Some(remote), branch) if remotes.lines().any(|name| name == remote) => {
+                (remote.as_str(), branch.clone())
+            }
+            (Some(branch), None) => (REMOTE_NAME, Some(branch.clone())),
+            (Some(remote), Some(_)) => return Err(Error::NoSuchRemote(remote.clone())),
+            (None, _) => (REMOTE_NAME, None),
+        };
+
+        let repo = match GitRepository::from_path(cwd, remote) {
+            Err(Error::LocalRemote(_, path)) => return Ok(path.to_string_lossy().to_string()),
+            repo => repo?,
+        };
+
+        if self.commit {
+            let sha = repo
+                .resolve_commit("HEAD")
+                .ok_or_else(|| Error::NotFound(messages::text("no-commits", &[])))?;
+
+            return Ok(repo.commit_url(&sha));
+        }
+
+        let branch = branch.or_else(|| repo.branch.clone());
+
+        Ok(match branch {
+            Some(branch) if branch != repo.default_branch() => {
+                let repo = repo.pushed_to(&branch).unwrap_or(repo);
+
+                repo.warn_if_unpushed(&branch);
+                repo.tree_url(&branch, "")
+            }
+            _ => repo.http_url(),
+        })
+    }
+}
@@ -0,0 +1,90 @@
+/// Match a `/`-separated path against a glob.
+///
+/// `*` and `?` stay within a path segment, while a `**` segment spans any number of them.
+pub fn matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+
+    segments(&pattern, &path)
+}
+
+fn segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments(rest, &path[skip..])),
+        Some((first, rest)) => path.split_first().is_some_and(|(segment, remaining)| {
+            wildcard(first, segment) && segments(rest, remaining)
+        }),
+    }
+}
+
+fn wildcard(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    // Classic backtracking match, remembering the last `*` to retry from.
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::matches;
+
+    #[test]
+    fn keeps_wildcards_within_a_segment() {
+        for (pattern, path, expected) in [
+            ("main.rs", "main.rs", true),
+            ("main.rs", "main.rsx", false),
+            ("*.rs", "main.rs", true),
+            ("*.rs", "src/main.rs", false),
+            ("src/*", "src/main.rs", true),
+            ("src/*", "src/bin/main.rs", false),
+            ("?.md", "a.md", true),
+            ("?.md", "ab.md", false),
+            ("a*b*c", "axxbyyc", true),
+            ("a*b*c", "axxbyy", false),
+        ] {
+            assert_eq!(matches(pattern, path), expected, "{pattern} {path}");
+        }
+    }
+
+    #[test]
+    fn spans_segments_with_double_stars() {
+        for (pattern, path, expected) in [
+            ("**/main.rs", "main.rs", true),
+            ("**/main.rs", "src/bin/main.rs", true),
+            ("src/**/*.rs", "src/main.rs", true),
+            ("src/**/*.rs", "src/a/b/main.rs", true),
+            ("src/**/*.rs", "lib/a/main.rs", false),
+            ("src/**", "src/a/b", true),
+            ("src/**", "lib/a", false),
+            ("**", "any/path/at/all", true),
+        ] {
+            assert_eq!(matches(pattern, path), expected, "{pattern} {path}");
+        }
+    }
+}
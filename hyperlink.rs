@@ -0,0 +1,81 @@
+use std::env;
+use std::io::{stdout, IsTerminal};
+
+/// Path segments that end a repository's path in provider URLs, and whether the next segment
+/// is the ref being viewed.
+const MARKERS: [(&str, bool); 14] = [
+    ("-", false),
+    ("tree", true),
+    ("blob", true),
+    ("src", true),
+    ("commit", true),
+    ("commits", true),
+    ("compare", false),
+    ("pull", false),
+    ("pulls", false),
+    ("pull-requests", false),
+    ("merge_requests", false),
+    ("releases", false),
+    ("issues", false),
+    ("actions", false),
+];
+
+/// Whether stdout is a terminal that can be trusted with OSC 8 escapes.
+pub fn supported() -> bool {
+    stdout().is_terminal() && env::var("TERM").map_or(true, |term| term != "dumb")
+}
+
+/// `url` as a clickable OSC 8 link, labelled `org/name@ref` when it points into a repository.
+pub fn format(url: &str) -> String {
+    link(url, &label(url).unwrap_or_else(|| url.to_string()))
+}
+
+/// `url` as a clickable OSC 8 link showing `text`.
+pub fn link(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// `org/name@ref` for a URL into a repository, or `org/name` for the rest of its pages.
+pub fn label(url: &str) -> Option<String> {
+    let path = url
+        .strip_prefix("https://")?
+        .split(['?', '#'])
+        .next()?
+        .split_once('/')?
+        .1;
+
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    // A repository path has at least an owner and a name.
+    let end = segments
+        .iter()
+        .skip(2)
+        .position(|segment| MARKERS.iter().any(|(marker, _)| marker == segment))
+        .map_or(segments.len(), |index| index + 2);
+
+    if end < 2 {
+        return None;
+    }
+
+    let repository = segments[..end].join("/");
+
+    let rest = segments[end..]
+        .strip_prefix(&["-"])
+        .unwrap_or(&segments[end..]);
+
+    let rev = match rest {
+        [marker, rev, ..]
+            if MARKERS
+                .iter()
+                .any(|(name, has_rev)| *has_rev && name == marker) =>
+        {
+            Some(*rev)
+        }
+        _ => None,
+    };
+
+    Some(match rev {
+        Some(rev) => format!("{repository}@{rev}"),
+        None => repository,
+    })
+}
@@ -0,0 +1,38 @@
+use std::env;
+
+use clap::{CommandFactory, ValueEnum};
+
+use crate::CLI;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Fish,
+    Zsh,
+}
+
+/// The snippet that wires a shell up with an `o` wrapper, a Ctrl-O binding that opens the word
+/// under the cursor, and completions.
+pub fn script(shell: Shell) -> String {
+    let template = match shell {
+        Shell::Bash => include_str!("shell/init.bash"),
+        Shell::Fish => include_str!("shell/init.fish"),
+        Shell::Zsh => include_str!("shell/init.zsh"),
+    };
+
+    // Call back into this exact binary, so a system `open` earlier in $PATH can't shadow it.
+    let exe = env::current_exe().map_or_else(
+        |_| "open".to_string(),
+        |path| path.to_string_lossy().to_string(),
+    );
+
+    let commands = CLI::command()
+        .get_subcommands()
+        .map(|command| command.get_name().to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    template
+        .replace("@EXE@", &format!("'{}'", exe.replace('\'', r"'\''")))
+        .replace("@COMMANDS@", &commands)
+}
@@ -0,0 +1,407 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// How deeply arrays and objects may nest, well beyond any real response, so that a hostile one
+/// can't recurse the parser off the end of the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Just enough JSON to read what plugins and other tools hand back, and to answer in kind.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut parser = Parser {
+            chars: input.chars().peekable(),
+            depth: 0,
+        };
+
+        let value = parser.value()?;
+
+        parser.whitespace();
+        parser.chars.peek().is_none().then_some(value)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Self::Object(members) => members.iter().find_map(|(k, v)| (k == key).then_some(v)),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Self::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// A whole number, e.g. an issue's, as JSON has only floats.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::Number(number) => number.to_string().parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(string) => Some(string),
+            _ => None,
+        }
+    }
+}
+
+/// Compact JSON, as `Display` so it can be printed or `to_string()`ed directly.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Number(value) if value.is_finite() => write!(f, "{value}"),
+            // JSON has no NaN or infinity.
+            Self::Null | Self::Number(_) => write!(f, "null"),
+            Self::String(value) => write_string(f, value),
+            Self::Array(values) => {
+                write!(f, "[")?;
+
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+
+                write!(f, "]")
+            }
+            Self::Object(members) => {
+                write!(f, "{{")?;
+
+                for (index, (key, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+
+    for c in value.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", u32::from(c))?,
+            c => write!(f, "{c}")?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    /// How many arrays and objects the parser is inside.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn whitespace(&mut self) {
+        while self.chars.next_if(char::is_ascii_whitespace).is_some() {}
+    }
+
+    fn expect(&mut self, expected: &str) -> Option<()> {
+        expected
+            .chars()
+            .all(|c| self.chars.next() == Some(c))
+            .then_some(())
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.whitespace();
+
+        match self.chars.peek()? {
+            'n' => self.expect("null").map(|()| Value::Null),
+            't' => self.expect("true").map(|()| Value::Bool(true)),
+            'f' => self.expect("false").map(|()| Value::Bool(false)),
+            '"' => self.string().map(Value::String),
+            '[' => self.nested(Self::array).map(Value::Array),
+            '{' => self.nested(Self::object).map(Value::Object),
+            _ => self.number().map(Value::Number),
+        }
+    }
+
+    /// Parse an array or object with `parse`, one level deeper, up to `MAX_DEPTH`.
+    fn nested<T>(&mut self, parse: fn(&mut Self) -> Option<T>) -> Option<T> {
+        if self.depth == MAX_DEPTH {
+            return None;
+        }
+
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+
+        value
+    }
+
+    fn number(&mut self) -> Option<f64> {
+        let mut number = String::new();
+
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            number.push(c);
+        }
+
+        number.parse().ok()
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.expect("\"")?;
+
+        let mut string = String::new();
+
+        loop {
+            match self.chars.next()? {
+                '"' => return Some(string),
+                '\\' => match self.chars.next()? {
+                    'b' => string.push('\u{8}'),
+                    'f' => string.push('\u{c}'),
+                    'n' => string.push('\n'),
+                    'r' => string.push('\r'),
+                    't' => string.push('\t'),
+                    'u' => {
+                        let high = self.hex()?;
+
+                        // Characters outside the BMP arrive as a UTF-16 surrogate pair.
+                        let code = if (0xD800..0xDC00).contains(&high) {
+                            self.expect("\\u")?;
+                            let low = self.hex()?;
+                            0x10000 + ((high - 0xD800) << 10) + (low.checked_sub(0xDC00)?)
+                        } else {
+                            high
+                        };
+
+                        string.push(char::from_u32(code)?);
+                    }
+                    c => string.push(c),
+                },
+                c => string.push(c),
+            }
+        }
+    }
+
+    fn hex(&mut self) -> Option<u32> {
+        (0..4).try_fold(0, |code, _| {
+            Some(code * 16 + self.chars.next()?.to_digit(16)?)
+        })
+    }
+
+    fn array(&mut self) -> Option<Vec<Value>> {
+        self.expect("[")?;
+        self.whitespace();
+
+        let mut values = Vec::new();
+
+        if self.chars.next_if_eq(&']').is_some() {
+            return Some(values);
+        }
+
+        loop {
+            values.push(self.value()?);
+            self.whitespace();
+
+            match self.chars.next()? {
+                ',' => {}
+                ']' => return Some(values),
+                _ => return None,
+            }
+        }
+    }
+
+    fn object(&mut self) -> Option<Vec<(String, Value)>> {
+        self.expect("{")?;
+        self.whitespace();
+
+        let mut members = Vec::new();
+
+        if self.chars.next_if_eq(&'}').is_some() {
+            return Some(members);
+        }
+
+        loop {
+            self.whitespace();
+            let key = self.string()?;
+
+            self.whitespace();
+            self.expect(":")?;
+
+            members.push((key, self.value()?));
+            self.whitespace();
+
+            match self.chars.next()? {
+                ',' => {}
+                '}' => return Some(members),
+                _ => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Value;
+
+    fn string(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    #[test]
+    fn parses_escapes() {
+        assert_eq!(
+            Value::parse(r#""a\"b\\c\/d\n\r\t\b\f""#),
+            Some(string("a\"b\\c/d\n\r\t\u{8}\u{c}"))
+        );
+        assert_eq!(Value::parse(r#""é中""#), Some(string("é中")));
+        assert_eq!(Value::parse(r#""😀""#), Some(string("😀")));
+    }
+
+    #[test]
+    fn rejects_bad_escapes() {
+        assert_eq!(Value::parse(r#""\u12""#), None);
+        assert_eq!(Value::parse(r#""\ud83d""#), None);
+        assert_eq!(Value::parse(r#""\ud83dA""#), None);
+        assert_eq!(Value::parse(r#""unterminated"#), None);
+    }
+
+    #[test]
+    fn writes_escapes() {
+        assert_eq!(
+            string("a\"b\\c\nd\te\u{1}").to_string(),
+            r#""a\"b\\c\nd\te\u0001""#
+        );
+    }
+
+    #[test]
+    fn round_trips_strings() {
+        let value = string("quote \" slash \\ newline \n emoji 😀 control \u{1f}");
+
+        assert_eq!(Value::parse(&value.to_string()), Some(value));
+    }
+
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(Value::parse("0"), Some(Value::Number(0.0)));
+        assert_eq!(Value::parse("-12"), Some(Value::Number(-12.0)));
+        assert_eq!(Value::parse("3.25"), Some(Value::Number(3.25)));
+        assert_eq!(Value::parse("1e3"), Some(Value::Number(1000.0)));
+        assert_eq!(Value::parse("2.5E-1"), Some(Value::Number(0.25)));
+        assert_eq!(Value::parse("-"), None);
+        assert_eq!(Value::parse("1e"), None);
+        assert_eq!(Value::parse("1.2.3"), None);
+    }
+
+    #[test]
+    fn reads_whole_numbers() {
+        assert_eq!(Value::parse("123").and_then(|v| v.as_u64()), Some(123));
+        assert_eq!(Value::parse("1.5").and_then(|v| v.as_u64()), None);
+        assert_eq!(Value::parse("-1").and_then(|v| v.as_u64()), None);
+        assert_eq!(string("1").as_u64(), None);
+    }
+
+    #[test]
+    fn writes_numbers() {
+        assert_eq!(Value::Number(42.0).to_string(), "42");
+        assert_eq!(Value::Number(-0.5).to_string(), "-0.5");
+        assert_eq!(Value::Number(f64::NAN).to_string(), "null");
+        assert_eq!(Value::Number(f64::INFINITY).to_string(), "null");
+    }
+
+    #[test]
+    fn parses_nesting() {
+        let value = Value::parse(
+            r#" { "runs": [ { "id": 1, "url": "https://x", "ok": true }, [] , null ], "e": {} } "#,
+        )
+        .expect("valid JSON");
+
+        let runs = value
+            .get("runs")
+            .and_then(Value::as_array)
+            .expect("an array");
+
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0].get("id").and_then(Value::as_u64), Some(1));
+        assert_eq!(
+            runs[0].get("url").and_then(Value::as_str),
+            Some("https://x")
+        );
+        assert_eq!(runs[0].get("ok"), Some(&Value::Bool(true)));
+        assert_eq!(runs[1], Value::Array(Vec::new()));
+        assert_eq!(runs[2], Value::Null);
+        assert_eq!(value.get("e"), Some(&Value::Object(Vec::new())));
+        assert_eq!(value.get("missing"), None);
+    }
+
+    #[test]
+    fn rejects_malformed_nesting() {
+        for input in [
+            "[1, 2",
+            "[1 2]",
+            "{\"a\" 1}",
+            "{\"a\": 1,}",
+            "{1: 2}",
+            "[1] x",
+            "",
+        ] {
+            assert_eq!(Value::parse(input), None, "{input}");
+        }
+    }
+
+    #[test]
+    fn round_trips_nesting() {
+        let value = Value::Object(vec![
+            (
+                "list".to_string(),
+                Value::Array(vec![Value::Number(1.0), Value::Null]),
+            ),
+            (
+                "inner".to_string(),
+                Value::Object(vec![("flag".to_string(), Value::Bool(false))]),
+            ),
+        ]);
+
+        assert_eq!(
+            value.to_string(),
+            r#"{"list":[1,null],"inner":{"flag":false}}"#
+        );
+        assert_eq!(Value::parse(&value.to_string()), Some(value));
+    }
+
+    #[test]
+    fn limits_nesting() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+
+        assert!(Value::parse(&nested(super::MAX_DEPTH)).is_some());
+        assert_eq!(Value::parse(&nested(super::MAX_DEPTH + 1)), None);
+        assert_eq!(Value::parse(&"{\"a\":".repeat(100_000)), None);
+        assert_eq!(Value::parse(&"[".repeat(1_000_000)), None);
+    }
+}
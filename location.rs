@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A local file, optionally with the `:line[:column]` suffix that compilers and grep emit, or a
+/// `:start-end` line range.
+#[derive(Debug)]
+pub struct Location {
+    pub path: PathBuf,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    /// The last line of a `:start-end` range, which editors ignore.
+    pub end_line: Option<u32>,
+}
+
+impl Location {
+    /// Parse `path[:line[:column]]` or `path:start-end`, returning `None` if the path doesn't
+    /// exist.
+    pub fn parse(arg: &str) -> Option<Self> {
+        let mut path = arg;
+        let mut numbers = Vec::new();
+        let mut end_line = None;
+
+        while !Path::new(path).exists() {
+            let (head, tail) = path.rsplit_once(':')?;
+
+            match tail.split_once('-') {
+                // A range is only ever the last suffix.
+                Some((start, end)) if numbers.is_empty() => {
+                    numbers.push(start.parse::<u32>().ok()?);
+                    end_line = Some(end.parse::<u32>().ok()?);
+                }
+                Some(_) => return None,
+                None if end_line.is_some() => return None,
+                None => numbers.insert(0, tail.parse::<u32>().ok()?),
+            }
+
+            if numbers.len() > 2 {
+                return None;
+            }
+
+            path = head;
+        }
+
+        Some(Self {
+            path: fs::canonicalize(path).ok()?,
+            line: numbers.first().copied(),
+            column: numbers.get(1).copied(),
+            end_line,
+        })
+    }
+}
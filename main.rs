@@ -13,26 +13,323 @@
 )]
 
 use std::env;
-use std::io::{stdout, Write};
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, stdout, IsTerminal, Write};
 use std::net::TcpStream;
-use std::process::{self, Command, Stdio};
+use std::path::{Path, PathBuf};
+use std::process::{self, Stdio};
+use std::time::Duration;
 
-use clap::Parser;
-use parse_git_url::GitUrl;
-use shellexpand::tilde;
+use clap::{CommandFactory, Parser, Subcommand};
+
+use crate::clipboard::CopyMode;
+use crate::editor::Editor;
+use crate::git_open::GitOpen;
+use crate::init::Shell;
+use crate::json::Value;
+use crate::location::Location;
+use crate::paths::{expand_tilde, PathMapper};
+use crate::provider::Provider;
+use crate::repo::{
+    ArchiveFormat, ChangeFormat, Error, GitRepository, Page, PrTab, Section, UrlForm,
+};
+use crate::resolve::{is_sha, is_url, Kind, Resolution};
+use crate::shorthand::Shorthand;
+
+mod api;
+mod brew;
+mod cache;
+mod cargo;
+mod ci;
+mod clipboard;
+mod config;
+mod confirm;
+mod daemon;
+mod editor;
+mod formats;
+mod git;
+mod git_open;
+mod glob;
+mod hyperlink;
+mod init;
+mod json;
+mod location;
+mod messages;
+mod network;
+mod opener;
+mod osc52;
+mod owners;
+mod paths;
+mod picker;
+mod plugins;
+mod provider;
+mod qr;
+mod repo;
+mod resolve;
+mod shorthand;
+mod ssh;
+mod submodules;
+mod trailers;
+mod watch;
+mod watchdog;
 
 const LOCALHOST: &str = "localhost";
-const OPEN: &str = "/usr/bin/open";
+/// Where `--clone` puts repositories without `magic-opener.projects-dir`, as `<org>/<name>`.
+const PROJECTS_DIR: &str = "~/src";
+/// The port `open` relays targets to over SSH, forwarded back to where `open daemon` listens.
 const PORT: u16 = 2226;
+/// Seconds `prompt-data` lets any git command take, since a prompt can't wait.
+const PROMPT_TIMEOUT: u64 = 1;
 const REMOTE_NAME: &str = "origin";
 
 #[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None, disable_help_flag = true)]
-#[allow(clippy::upper_case_acronyms)]
+#[clap(
+    author,
+    version,
+    about,
+    long_about = None,
+    disable_help_flag = true,
+    disable_help_subcommand = true,
+    args_conflicts_with_subcommands = true
+)]
+#[allow(clippy::upper_case_acronyms, clippy::struct_excessive_bools)]
 struct CLI {
-    #[clap(short, long, help = "Print the URL to stdout instead of opening it.")]
+    #[clap(
+        short,
+        long,
+        global = true,
+        help = "Print the URL to stdout instead of opening it."
+    )]
     print: bool,
 
+    #[clap(
+        long,
+        global = true,
+        help = "Print targets NUL-terminated for `xargs -0`, plain and without asking which reading was meant."
+    )]
+    print0: bool,
+
+    #[clap(
+        long,
+        global = true,
+        conflicts_with = "print0",
+        help = "Print targets as clickable OSC 8 links, even where the terminal's support can't be detected."
+    )]
+    hyperlink: bool,
+
+    #[clap(
+        long,
+        value_enum,
+        global = true,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "only",
+        help = "Copy the URL, or what --format or --json prints, to the clipboard instead of opening it, or as well with --copy=open."
+    )]
+    copy: Option<CopyMode>,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Print the URL as a QR code, to open it on a phone, instead of opening it."
+    )]
+    qr: bool,
+
+    #[clap(
+        long,
+        value_enum,
+        global = true,
+        help = "Open a file argument in this editor, at its :line:column if given."
+    )]
+    editor: Option<Editor>,
+
+    #[clap(
+        long,
+        value_enum,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "tar",
+        help = "Open the source archive for the current ref, or for the given commit, branch or tag."
+    )]
+    archive: Option<ArchiveFormat>,
+
+    #[clap(
+        long,
+        global = true,
+        conflicts_with = "diff",
+        help = "Open a commit or pull request as a patch, e.g. to pipe into `git am`."
+    )]
+    patch: bool,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Open a commit or pull request as a plain diff."
+    )]
+    diff: bool,
+
+    #[clap(
+        short,
+        long,
+        value_name = "NAME",
+        help = "Open the tree view of this branch instead of the current one."
+    )]
+    branch: Option<String>,
+
+    #[clap(
+        long,
+        value_name = "NAME",
+        conflicts_with = "branch",
+        help = "Open the tree view of this tag."
+    )]
+    tag: Option<String>,
+
+    #[clap(
+        long,
+        value_name = "NUMBER",
+        help = "Open an issue by number. On GitLab, a `#123` argument is an issue too, and `!123` a merge request."
+    )]
+    issue: Option<u64>,
+
+    #[clap(
+        long,
+        help = "Open the CI run for the current commit, or its checks if the provider's API can't find one."
+    )]
+    ci: bool,
+
+    #[clap(
+        long,
+        help = "Open the form for a new pull request from the current branch, once it's pushed."
+    )]
+    new_pr: bool,
+
+    #[clap(
+        long,
+        help = "Link files and trees at the current commit's hash rather than the branch, so the link doesn't move."
+    )]
+    permalink: bool,
+
+    #[clap(
+        long,
+        conflicts_with = "local",
+        help = "Open a file tracked in a repository as plain text rather than its web page."
+    )]
+    raw: bool,
+
+    #[clap(
+        long,
+        conflicts_with_all = ["local", "raw"],
+        help = "Open the commit history of a file tracked in a repository."
+    )]
+    history: bool,
+
+    #[clap(
+        long,
+        help = "Open a file tracked in a repository locally instead of on the web."
+    )]
+    local: bool,
+
+    #[clap(
+        long = "crate",
+        value_name = "MEMBER",
+        help = "Open the directory of this member crate of the current Cargo workspace."
+    )]
+    krate: Option<String>,
+
+    #[clap(
+        long,
+        value_name = "WORD",
+        help = "Print what a URL, commit, #PR or file argument resolves to, without opening it."
+    )]
+    resolve_only: Option<String>,
+
+    #[clap(
+        long = "as",
+        value_enum,
+        value_name = "FORM",
+        help = "Print the repository's URL, or a repository URL argument, as an ssh, https or git clone URL, or its API URL."
+    )]
+    url_form: Option<UrlForm>,
+
+    #[clap(
+        long,
+        help = "Clone the repository of a web or clone URL argument over SSH, into the directory after it or <projects-dir>/<org>/<name>, and print where it went."
+    )]
+    clone: bool,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Take the preferred reading of an ambiguous argument instead of asking which was meant."
+    )]
+    first: bool,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Print an object per target with its repository, branches, kind and URL instead of opening it, or with the error."
+    )]
+    json: bool,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Print the URL as md, html or plain, a magic-opener.format.<name>, or a template of {url}, {title}, {org}, {name}, {branch}, {sha}, {pr} and the --json fields."
+    )]
+    format: Option<String>,
+
+    #[clap(
+        long,
+        help = "Print every URL the argument could resolve to, or without one, the repository's own pages, labelled by kind, instead of picking one."
+    )]
+    all: bool,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Never touch the network: use only local git data. Defaults to magic-opener.offline."
+    )]
+    offline: bool,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Look up the remote's default branch again instead of using the cached one."
+    )]
+    refresh: bool,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Don't warn when a file link won't match uncommitted changes in the working copy."
+    )]
+    force: bool,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Open without asking, whatever the magic-opener.confirm-* settings say."
+    )]
+    yes: bool,
+
+    #[clap(
+        long,
+        global = true,
+        value_name = "PROVIDER",
+        help = "Force the URL layout: github, gitlab, bitbucket, gitea or custom:<template-set>."
+    )]
+    provider: Option<Provider>,
+
+    #[clap(
+        long,
+        global = true,
+        default_value = REMOTE_NAME,
+        help = "Remote to build URLs for."
+    )]
+    remote: String,
+
     #[clap(
         allow_hyphen_values = true,
         trailing_var_arg = true,
@@ -40,102 +337,1460 @@ struct CLI {
         help = "Path to a Git repository. Otherwise the current directory will be used."
     )]
     path: Vec<String>,
+
+    #[clap(subcommand)]
+    command: Option<Commands>,
 }
 
-fn git_url() -> Option<String> {
-    Command::new("git")
-        .args(["remote", "get-url", REMOTE_NAME])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .ok()
-        .and_then(|output| GitUrl::parse(String::from_utf8_lossy(&output.stdout).trim_end()).ok())
-        .and_then(|parsed| {
-            parsed
-                .host
-                .map(|host| format!("https://{}/{}", host, parsed.fullname))
-        })
+/// How targets are printed instead of opened: a line each, NUL-terminated with `--print0`, or
+/// as clickable links with `--hyperlink`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Print {
+    Lines,
+    Nul,
+    Hyperlink,
 }
 
-fn main() {
-    let args = CLI::parse();
+impl Print {
+    /// Write `text` to stdout, ended as asked.
+    fn emit(self, text: &str) {
+        match self {
+            Self::Lines | Self::Hyperlink => println!("{text}"),
+            Self::Nul => print!("{text}\0"),
+        }
+    }
 
-    let current_dir = env::current_dir()
-        .expect("Failed to get current directory")
-        .to_string_lossy()
-        .to_string();
+    /// Write a target, as an OSC 8 link when asked to or the terminal is known to support them.
+    fn target(self, target: &str) {
+        let link = is_url(target)
+            && match self {
+                Self::Lines => hyperlink::supported(),
+                Self::Nul => false,
+                Self::Hyperlink => true,
+            };
 
-    let remote_path = if args.path.is_empty() {
-        match git_url() {
-            Some(url) => url,
-            None => current_dir.clone(),
+        if link {
+            self.emit(&hyperlink::format(target));
+        } else {
+            self.emit(target);
         }
-    } else {
-        match args.path.join(" ") {
-            path if path == "." => current_dir.clone(),
-            path => path,
+    }
+}
+
+impl CLI {
+    /// Whether and how to print targets rather than open them.
+    fn print(&self) -> Option<Print> {
+        if self.print0 {
+            Some(Print::Nul)
+        } else if self.hyperlink {
+            Some(Print::Hyperlink)
+        } else {
+            self.print.then_some(Print::Lines)
         }
-    };
+    }
 
-    if remote_path.starts_with('-') {
-        let command = if remote_path == "--help" {
-            vec!["-h"]
+    /// `rev`, or with `--permalink`, the full hash of the commit it names.
+    fn pinned(&self, repo: &GitRepository, rev: &str) -> String {
+        self.permalink
+            .then(|| repo.resolve_commit(rev))
+            .flatten()
+            .unwrap_or_else(|| rev.to_string())
+    }
+
+    /// The raw form `--patch` or `--diff` asks for, if either.
+    fn change(&self) -> Option<ChangeFormat> {
+        if self.patch {
+            Some(ChangeFormat::Patch)
+        } else if self.diff {
+            Some(ChangeFormat::Diff)
         } else {
-            args.path.iter().map(String::as_str).collect()
-        };
+            None
+        }
+    }
+
+    /// `page`, a commit or pull request page of `repo`, as `--patch` or `--diff` asks.
+    fn change_as(&self, repo: &GitRepository, page: String) -> Result<String, Error> {
+        match self.change() {
+            Some(format) => repo.change_as(&page, format),
+            None => Ok(page),
+        }
+    }
+}
+
+/// The command line, with a `--` put before a subcommand's name that's also a path, so that
+/// `open packages` in a monorepo opens its `packages/` directory as the launcher `open` stands
+/// in for would. Clap only takes the first word that isn't a flag for a subcommand.
+fn arguments(
+    args: impl IntoIterator<Item = OsString>,
+    exists: impl Fn(&Path) -> bool,
+) -> Vec<OsString> {
+    let mut args: Vec<OsString> = args.into_iter().collect();
+
+    let Some(index) = args
+        .iter()
+        .skip(1)
+        .position(|arg| !arg.to_string_lossy().starts_with('-'))
+        .map(|index| index + 1)
+    else {
+        return args;
+    };
 
-        let output = Command::new(OPEN)
-            .args(command)
-            .stderr(Stdio::inherit())
-            .output()
-            .expect("Failed to run command");
+    let command = CLI::command();
 
-        stdout()
-            .write_all(&output.stderr)
-            .expect("Failed to write to stdout");
+    let is_subcommand = command.get_subcommands().any(|subcommand| {
+        subcommand.get_name() == args[index]
+            || subcommand
+                .get_all_aliases()
+                .any(|alias| alias == args[index])
+    });
 
-        process::exit(0);
+    if is_subcommand && exists(Path::new(&args[index])) {
+        args.insert(index, OsString::from("--"));
     }
 
-    let ssh_tty = env::var_os("SSH_TTY").is_some();
+    args
+}
 
-    let remote_path = if remote_path.contains("://") {
-        remote_path.clone()
-    } else if ssh_tty {
-        //
-        let client_home = env::var("SSH_CLIENT_HOME")
-            .expect("No $SSH_CLIENT_HOME set! It must be set in the SSH client config.");
+#[derive(Subcommand, Debug)]
+enum Commands {
+    #[clap(about = "Open a pull request (or GitLab merge request) by number.")]
+    Pr {
+        number: u64,
 
-        let expanded_path = tilde(&remote_path);
+        #[clap(
+            long,
+            help = "Fetch the pull request into a local branch and switch to it instead."
+        )]
+        checkout: bool,
+    },
 
-        if expanded_path.starts_with("/bits") {
-            format!("{client_home}/Mounts{expanded_path}")
-        } else {
-            expanded_path.into_owned()
+    #[clap(
+        about = "Open a pull request's conversation, checks and changed files together. Set magic-opener.review-tabs to change them."
+    )]
+    Review { number: u64 },
+
+    #[clap(about = "Compare a branch, or a fork's `user:branch`, against the default branch.")]
+    Compare {
+        head: String,
+
+        #[clap(
+            long,
+            help = "Base branch to compare against. Defaults to the default branch."
+        )]
+        base: Option<String>,
+    },
+
+    #[clap(about = "Open the profile of whoever CODEOWNERS says owns a file.")]
+    Owners { file: PathBuf },
+
+    #[clap(about = "Open the CI configuration, on the web at the current branch or in --editor.")]
+    CiConfig,
+
+    #[clap(about = "Open the repository's package registry, or a published package.")]
+    Packages {
+        name: Option<String>,
+
+        #[clap(
+            long = "type",
+            default_value = "container",
+            help = "Package type, e.g. container, npm, maven, rubygems, nuget."
+        )]
+        package_type: String,
+    },
+
+    #[clap(
+        about = "Open pull requests from dependency bots. Set magic-opener.deps-filter to change the search."
+    )]
+    Deps,
+
+    #[clap(
+        about = "Open one of the repository's pages, e.g. its issues, pull requests or CI runs."
+    )]
+    Page {
+        #[clap(value_enum)]
+        section: Section,
+    },
+
+    #[clap(about = "Open the repository's license.")]
+    License,
+
+    #[clap(about = "Open the repository's security policy and advisories.")]
+    Security,
+
+    #[clap(
+        about = "Print what an argument resolves to without opening it: the interface for editors and other tools."
+    )]
+    Resolve {
+        word: String,
+
+        #[clap(long, help = "Allow network lookups, which are off by default.")]
+        network: bool,
+    },
+
+    #[clap(
+        about = "Print `org/name<TAB>branch<TAB>#pr` for a shell prompt, from local state only. Prints nothing outside a repository."
+    )]
+    PromptData,
+
+    #[clap(about = "Print the shell integration script, e.g. `eval \"$(open init zsh)\"`.")]
+    Init {
+        #[clap(value_enum)]
+        shell: Shell,
+    },
+
+    #[clap(
+        about = "Compare ORIG_HEAD with HEAD, to review what a rebase or merge just brought in."
+    )]
+    Rebased,
+
+    #[clap(about = "Compare the current branch with its upstream: the commits not pushed yet.")]
+    Ahead,
+
+    #[clap(about = "Compare the upstream with the current branch: the commits not pulled yet.")]
+    Behind,
+
+    #[clap(
+        about = "Open the issues, changes and tickets a commit's trailers refer to, e.g. `Fixes: #123`, and the issues its message closes."
+    )]
+    Links {
+        #[clap(default_value = "HEAD")]
+        commit: String,
+    },
+
+    #[clap(about = "Pick one of the repository's submodules and open its web page.")]
+    Submodules,
+
+    #[clap(about = "Open the pull request that merged a commit.")]
+    Merged {
+        #[clap(default_value = "HEAD")]
+        commit: String,
+    },
+
+    #[clap(about = "Open the first tag or release that contains a commit.")]
+    Contains { commit: String },
+
+    #[clap(about = "Open the runs of a GitHub Actions workflow for the current branch.")]
+    Runs {
+        #[clap(help = "Workflow file, e.g. ci.yml or .github/workflows/ci.yml.")]
+        workflow: PathBuf,
+    },
+
+    #[clap(about = "Open a Homebrew formula or cask's homepage.")]
+    Brew {
+        name: String,
+
+        #[clap(long, help = "Open its page on formulae.brew.sh instead.")]
+        formulae: bool,
+    },
+
+    #[clap(
+        about = "Watch a directory, e.g. a docs build's output, and open each new file matching a pattern."
+    )]
+    Watch {
+        dir: PathBuf,
+
+        #[clap(
+            long,
+            default_value = "*",
+            help = "Glob for new files, matched against the file name, or the path under the directory if it has a /."
+        )]
+        pattern: String,
+
+        #[clap(long, default_value = "1", help = "Seconds between checks.")]
+        interval: u64,
+    },
+
+    #[clap(
+        about = "Listen on localhost:2226 and open the targets relayed from SSH sessions: the other end of `open` over SSH."
+    )]
+    Daemon,
+
+    #[clap(about = "Work with issues.")]
+    Issue {
+        #[clap(subcommand)]
+        action: IssueAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum IssueAction {
+    #[clap(about = "Open the new-issue form, prefilled from the given fields.")]
+    New {
+        #[clap(long, help = "Issue title.")]
+        title: Option<String>,
+
+        #[clap(long, help = "Read the issue body from this file.")]
+        body_file: Option<PathBuf>,
+
+        #[clap(long = "label", help = "Label to apply; may be repeated.")]
+        labels: Vec<String>,
+
+        #[clap(long, help = "Issue template to use.")]
+        template: Option<String>,
+    },
+}
+
+/// Resolve a subcommand to the URLs it should open.
+fn run(
+    command: &Commands,
+    args: &CLI,
+    current_dir: &Path,
+    ssh_tty: bool,
+) -> Result<Vec<String>, Error> {
+    match command {
+        Commands::Init { shell } => {
+            print!("{}", init::script(*shell));
+            Ok(Vec::new())
         }
-    } else {
-        remote_path.clone()
-    };
+        Commands::Brew { name, formulae } => Ok(vec![brew::url(name, *formulae)]),
+        Commands::Daemon => {
+            daemon::serve(current_dir)?;
+            Ok(Vec::new())
+        }
+        Commands::Resolve { word, network } => {
+            if !network {
+                network::set_offline(true);
+            }
 
-    if args.print {
-        println!("{remote_path}");
-    } else if ssh_tty {
-        let mut stream = TcpStream::connect((LOCALHOST, PORT))
-            .expect("Unable to create a socket for localhost:2226");
+            print_resolution(word, args.json, current_dir, &args.remote)?;
 
-        stream
-            .write_all(remote_path.as_bytes())
-            .expect("Couldn't write remote path to socket.");
-    } else {
-        let mut args = vec![remote_path.as_str()];
+            Ok(Vec::new())
+        }
+        Commands::PromptData => {
+            network::set_offline(true);
+            watchdog::set_timeout(PROMPT_TIMEOUT);
 
-        if remote_path.contains("://") {
-            args.insert(0, "--background");
+            // A prompt redraws on every command, so a missing repository isn't an error.
+            if let Ok(repo) = GitRepository::from_path(current_dir, &args.remote) {
+                println!(
+                    "{}/{}\t{}\t{}",
+                    repo.org,
+                    repo.name,
+                    repo.branch.as_deref().unwrap_or_default(),
+                    repo.cached_pr()
+                        .map(|number| format!("#{number}"))
+                        .unwrap_or_default()
+                );
+            }
+
+            Ok(Vec::new())
+        }
+        Commands::Watch {
+            dir,
+            pattern,
+            interval,
+        } => {
+            watch::watch(
+                &current_dir.join(dir),
+                pattern,
+                Duration::from_secs(*interval),
+                |path| {
+                    open(
+                        &[path.to_string_lossy().to_string()],
+                        args.print(),
+                        ssh_tty,
+                        args.yes,
+                    );
+                },
+            )?;
+
+            Ok(Vec::new())
+        }
+        // The file may live in another repository than the current directory.
+        Commands::Owners { file } => run_in_repo(
+            command,
+            &GitRepository::containing(file, &args.remote)?,
+            args,
+            current_dir,
+            ssh_tty,
+        ),
+        command => run_in_repo(
+            command,
+            &GitRepository::from_path(current_dir, &args.remote)?,
+            args,
+            current_dir,
+            ssh_tty,
+        ),
+    }
+}
+
+/// Resolve a subcommand that works on the current repository.
+#[allow(clippy::too_many_lines)]
+fn run_in_repo(
+    command: &Commands,
+    repo: &GitRepository,
+    args: &CLI,
+    current_dir: &Path,
+    ssh_tty: bool,
+) -> Result<Vec<String>, Error> {
+    match command {
+        Commands::Init { .. }
+        | Commands::Brew { .. }
+        | Commands::Daemon
+        | Commands::Resolve { .. }
+        | Commands::PromptData
+        | Commands::Watch { .. } => {
+            unreachable!("handled without a repository")
+        }
+        Commands::Pr {
+            number,
+            checkout: true,
+        } => {
+            println!("{}", repo.checkout_pr(*number)?);
+            Ok(Vec::new())
+        }
+        Commands::Pr { number, .. } => Ok(vec![args.change_as(repo, repo.pr_url(*number))?]),
+        Commands::Review { number } => {
+            let tabs = config::get(current_dir, "review-tabs")
+                .unwrap_or_else(|| "conversation,checks,files".to_string());
+
+            Ok(tabs
+                .split(',')
+                .filter_map(PrTab::parse)
+                .filter_map(|tab| repo.pr_tab_url(*number, tab))
+                .collect())
+        }
+        Commands::Compare { head, base } => {
+            let base = base.clone().unwrap_or_else(|| repo.default_branch());
+
+            // A branch pushed to a fork is compared as the fork's `user:branch`.
+            let head = match repo.pushed_to(head) {
+                Some(fork) if !head.contains(':') && fork.org != repo.org => {
+                    format!("{}:{head}", fork.org)
+                }
+                _ => head.clone(),
+            };
+
+            Ok(vec![repo.compare_url(&base, &head)])
+        }
+        Commands::Owners { file } => {
+            let path = repo.relative_path(file).ok_or_else(|| {
+                Error::NotFound(messages::text(
+                    "not-in-repository",
+                    &[("path", &file.display())],
+                ))
+            })?;
+
+            let owners = owners::owners(repo, &path).ok_or_else(|| {
+                Error::NotFound(messages::text("no-code-owners", &[("path", &path)]))
+            })?;
+
+            Ok(owners.iter().map(|owner| repo.owner_url(owner)).collect())
+        }
+        Commands::CiConfig => {
+            let files = ci::config_files(repo, args.editor.is_some());
+
+            if files.is_empty() {
+                return Err(Error::NotFound(messages::text("no-ci-config", &[])));
+            }
+
+            if let Some(editor) = args.editor {
+                return Ok(files
+                    .iter()
+                    .filter_map(|file| Location::parse(&repo.root.join(file).to_string_lossy()))
+                    .map(|location| editor.url(&location, ssh_tty))
+                    .collect());
+            }
+
+            let rev = repo.head_ref();
+            let pushed = repo.pushed_to(&rev);
+            let repo = pushed.as_ref().unwrap_or(repo);
+
+            repo.warn_if_unpushed(&rev);
+
+            Ok(files
+                .iter()
+                .map(|file| {
+                    if repo.root.join(file).is_dir() {
+                        repo.tree_url(&rev, file)
+                    } else {
+                        repo.blob_url(&rev, file)
+                    }
+                })
+                .collect())
+        }
+        Commands::Packages { name, package_type } => {
+            Ok(vec![repo.packages_url(name.as_deref(), package_type)])
+        }
+        Commands::Deps => {
+            Ok(vec![repo.deps_url(
+                config::get(current_dir, "deps-filter").as_deref(),
+            )])
+        }
+        Commands::Rebased => {
+            let base = repo
+                .resolve_commit("ORIG_HEAD")
+                .ok_or_else(|| Error::NotFound(messages::text("no-orig-head", &[])))?;
+
+            let head = repo
+                .resolve_commit("HEAD")
+                .ok_or_else(|| Error::NotFound(messages::text("no-commits", &[])))?;
+
+            Ok(vec![repo.compare_url(&base, &head)])
+        }
+        Commands::Ahead | Commands::Behind => {
+            let branch = repo
+                .branch
+                .as_ref()
+                .ok_or_else(|| Error::NotFound(messages::text("not-on-branch", &[])))?;
+
+            let upstream = repo.upstream_branch().ok_or_else(|| {
+                Error::NotFound(messages::text("no-upstream", &[("branch", branch)]))
+            })?;
+
+            Ok(vec![if matches!(command, Commands::Ahead) {
+                repo.compare_url(&upstream, branch)
+            } else {
+                repo.compare_url(branch, &upstream)
+            }])
+        }
+        Commands::Page { section } => Ok(vec![repo.section_url(*section)?]),
+        Commands::License | Commands::Security => {
+            let (page, missing) = if matches!(command, Commands::License) {
+                (Page::License, "no-license")
+            } else {
+                (Page::Security, "no-security-policy")
+            };
+
+            let url = repo
+                .page_url(page)
+                .ok_or_else(|| Error::NotFound(messages::text(missing, &[])))?;
+
+            Ok(vec![url])
+        }
+        Commands::Links { commit } => {
+            let links = trailers::links(repo, commit)?;
+
+            if links.is_empty() {
+                return Err(Error::NotFound(messages::text(
+                    "no-links",
+                    &[("rev", commit)],
+                )));
+            }
+
+            Ok(links)
+        }
+        Commands::Submodules => Ok(vec![submodules::pick_url(repo)?]),
+        Commands::Merged { commit } => {
+            let sha = repo.resolve_commit(commit).ok_or_else(|| {
+                Error::NotFound(messages::text("no-such-commit", &[("rev", commit)]))
+            })?;
+
+            let number = repo.pr_for_commit(&sha).ok_or_else(|| {
+                Error::NotFound(messages::text("no-merging-pr", &[("rev", commit)]))
+            })?;
+
+            Ok(vec![args.change_as(repo, repo.pr_url(number))?])
+        }
+        Commands::Contains { commit } => {
+            let sha = repo.resolve_commit(commit).ok_or_else(|| {
+                Error::NotFound(messages::text("no-such-commit", &[("rev", commit)]))
+            })?;
+
+            let tag = repo.first_tag_containing(&sha).ok_or_else(|| {
+                Error::NotFound(messages::text("no-containing-tag", &[("rev", commit)]))
+            })?;
+
+            Ok(vec![repo.release_url(&tag)])
+        }
+        Commands::Runs { workflow } => {
+            let file = workflow.file_name().map_or_else(
+                || workflow.to_string_lossy().to_string(),
+                |name| name.to_string_lossy().to_string(),
+            );
+
+            Ok(vec![repo.workflow_runs_url(&file, repo.branch.as_deref())])
         }
+        Commands::Issue {
+            action:
+                IssueAction::New {
+                    title,
+                    body_file,
+                    labels,
+                    template,
+                },
+        } => {
+            let body = body_file
+                .as_ref()
+                .map(|path| fs::read_to_string(path).map_err(|err| Error::Io(path.clone(), err)))
+                .transpose()?;
+
+            Ok(vec![repo.new_issue_url(
+                title.as_deref(),
+                body.as_deref(),
+                labels,
+                template.as_deref(),
+            )])
+        }
+    }
+}
+
+/// Clone the repository of a web page or clone URL over SSH into `dir`, or the projects
+/// directory, returning where it is. An existing clone is left as it is.
+fn clone(cwd: &Path, url: Option<&String>, dir: Option<&Path>) -> Result<PathBuf, Error> {
+    let url = url.ok_or_else(|| Error::NotFound(messages::text("clone-needs-url", &[])))?;
+    let repo = GitRepository::from_url(cwd, url)?;
+
+    let path = dir.map_or_else(
+        || {
+            let projects =
+                config::get(cwd, "projects-dir").unwrap_or_else(|| PROJECTS_DIR.to_string());
+
+            PathBuf::from(expand_tilde(&projects))
+                .join(&repo.org)
+                .join(&repo.name)
+        },
+        |dir| cwd.join(dir),
+    );
+
+    if !path.exists() {
+        git::run(
+            cwd,
+            &[
+                "clone",
+                &repo.url_as(UrlForm::Ssh)?,
+                &path.to_string_lossy(),
+            ],
+        )?;
+    }
+
+    Ok(path)
+}
+
+/// What `arg` names. When it could be read several ways, e.g. as a file and as a commit, the
+/// user picks one, unless `--first` or `--print0` asks for the preferred one or there's no
+/// terminal to ask on.
+fn resolution(args: &CLI, arg: &str, cwd: &Path) -> Result<Option<Resolution>, Error> {
+    if args.first || args.print0 || !io::stdin().is_terminal() {
+        return Ok(resolve::resolve(arg, cwd, &args.remote));
+    }
+
+    let mut readings = resolve::readings(arg, cwd, &args.remote);
+
+    if readings.len() < 2 {
+        return Ok(readings.pop());
+    }
+
+    let choices: Vec<String> = readings
+        .iter()
+        .map(|reading| format!("{:<12} {}", reading.kind.label(), reading.target))
+        .collect();
+
+    let index = picker::pick(&messages::text("pick-reading", &[("arg", &arg)]), &choices)?;
+
+    Ok(Some(readings.swap_remove(index)))
+}
+
+/// Print what `word` resolves to, as `open resolve` does for tools calling it.
+fn print_resolution(word: &str, json: bool, cwd: &Path, remote: &str) -> Result<(), Error> {
+    let resolution = resolve::resolve(word, cwd, remote)
+        .ok_or_else(|| Error::NotFound(messages::text("nothing-found", &[("word", &word)])));
+
+    if !json {
+        println!("{}", resolution?.target);
+        return Ok(());
+    }
+
+    let field = |key: &str, value: &str| (key.to_string(), Value::String(value.to_string()));
+
+    let mut fields = vec![field("arg", word)];
+
+    match &resolution {
+        Ok(resolution) => {
+            fields.push(field("target", &resolution.target));
+            fields.extend(description(resolution, cwd, remote));
+        }
+        Err(err) => fields.push(field("error", &err.to_string())),
+    }
+
+    println!("{}", Value::Object(fields));
+
+    // The object already carries the error, so don't report it twice.
+    if resolution.is_err() {
+        process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// What `--json` says about a resolved target: the repository it's in, the branches when that's
+/// the local one, what kind of page it is, and its URL.
+fn description(resolution: &Resolution, cwd: &Path, remote: &str) -> Vec<(String, Value)> {
+    let target = &resolution.target;
+
+    let (repo, local) = repositories(target, cwd, remote);
+    let repo = repo.as_ref().or(local.as_ref());
+
+    let string =
+        |value: Option<&str>| value.map_or(Value::Null, |value| Value::String(value.to_string()));
+
+    [
+        ("host", string(repo.map(|repo| repo.host.as_str()))),
+        ("org", string(repo.map(|repo| repo.org.as_str()))),
+        ("name", string(repo.map(|repo| repo.name.as_str()))),
+        (
+            "branch",
+            string(local.as_ref().and_then(|local| local.branch.as_deref())),
+        ),
+        (
+            "default_branch",
+            string(local.map(|local| local.default_branch()).as_deref()),
+        ),
+        ("kind", string(Some(resolution.kind.label()))),
+        ("url", string(Some(target))),
+    ]
+    .into_iter()
+    .map(|(key, value)| (key.to_string(), value))
+    .collect()
+}
+
+/// The repository `target` is in, and the local one when it's the same, with its branches.
+fn repositories(
+    target: &str,
+    cwd: &Path,
+    remote: &str,
+) -> (Option<GitRepository>, Option<GitRepository>) {
+    if !is_url(target) {
+        return (
+            None,
+            GitRepository::containing(Path::new(target), remote).ok(),
+        );
+    }
+
+    let repo = GitRepository::from_url(cwd, target).ok();
+
+    let local = GitRepository::from_path(cwd, remote).ok().filter(|local| {
+        repo.as_ref().is_some_and(|repo| {
+            (&repo.host, &repo.org, &repo.name) == (&local.host, &local.org, &local.name)
+        })
+    });
+
+    (repo, local)
+}
+
+/// What a `--format` template can use: the `--json` fields, the `sha` and `pr` the target is
+/// about or else the local branch's, and a `title` to label a link with, e.g. `org/name#123`.
+fn variables(resolution: &Resolution, cwd: &Path, remote: &str) -> Vec<(String, String)> {
+    let mut vars: Vec<(String, String)> = description(resolution, cwd, remote)
+        .into_iter()
+        .map(|(key, value)| (key, value.as_str().unwrap_or_default().to_string()))
+        .collect();
+
+    let target = &resolution.target;
+    let page = resolve::page_of(target);
+    let id = |wanted| page.and_then(|(kind, id)| (kind == wanted).then_some(id)?);
+
+    let (_, local) = repositories(target, cwd, remote);
+
+    let sha = id(Kind::Commit)
+        .map(str::to_string)
+        .or_else(|| local.as_ref()?.resolve_commit("HEAD"));
+
+    let pr = id(Kind::PullRequest)
+        .map(str::to_string)
+        .or_else(|| Some(local.as_ref()?.cached_pr()?.to_string()));
+
+    vars.extend([
+        ("sha".to_string(), sha.unwrap_or_default()),
+        ("pr".to_string(), pr.unwrap_or_default()),
+        ("title".to_string(), title(target)),
+    ]);
+
+    vars
+}
+
+/// A label for a link to `target`, e.g. `org/name#123` for a pull request or issue, or
+/// `org/name@ref` into a tree.
+fn title(target: &str) -> String {
+    let title = match resolve::page_of(target) {
+        Some((Kind::PullRequest | Kind::Issue, Some(number))) => {
+            hyperlink::label(target).map(|repository| format!("{repository}#{number}"))
+        }
+        _ => hyperlink::label(target),
+    };
+
+    title.unwrap_or_else(|| target.to_string())
+}
+
+/// `targets` as `--json` objects or in the `--format` asked for, or `None` when neither was.
+fn rendered(args: &CLI, cwd: &Path, targets: &[String]) -> Result<Option<Vec<String>>, Error> {
+    let template = match &args.format {
+        Some(format) => Some(formats::template(cwd, format).ok_or_else(|| {
+            Error::NotFound(messages::text("no-such-format", &[("format", format)]))
+        })?),
+        None if args.json => None,
+        None => return Ok(None),
+    };
+
+    let render = |target: &String| {
+        let resolution = Resolution {
+            kind: resolve::kind_of(target),
+            target: target.clone(),
+        };
+
+        match &template {
+            Some(template) => formats::render(template, &variables(&resolution, cwd, &args.remote)),
+            None => Value::Object(description(&resolution, cwd, &args.remote)).to_string(),
+        }
+    };
+
+    Ok(Some(targets.iter().map(render).collect()))
+}
+
+/// Print `targets` as `--json`, `--format` or `--qr` asks, and copy them as `--copy` asks,
+/// returning whether that's all, or they're to be opened or printed plain too.
+fn print_as_asked(args: &CLI, cwd: &Path, targets: &[String]) -> Result<bool, Error> {
+    let rendered = rendered(args, cwd, targets)?;
+
+    if args.copy.is_some() {
+        // Plain URLs go along as titled links too, where the clipboard takes them.
+        let links: Vec<(String, String)> = if rendered.is_none() {
+            targets
+                .iter()
+                .filter(|target| is_url(target))
+                .map(|target| (title(target), target.clone()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        clipboard::copy(&rendered.as_deref().unwrap_or(targets).join("\n"), &links)?;
+    }
+
+    if args.qr {
+        for target in targets {
+            println!("{}{target}", qr::render(target)?);
+        }
+
+        return Ok(true);
+    }
+
+    if let Some(lines) = &rendered {
+        let print = args.print().unwrap_or(Print::Lines);
+
+        for line in lines {
+            print.emit(line);
+        }
+
+        return Ok(true);
+    }
+
+    Ok(args.copy == Some(CopyMode::Only) && args.print().is_none())
+}
+
+/// Report `err` and exit, as an object on stdout under `--json` so tools needn't scrape stderr.
+fn fail(err: &Error, json: bool) -> ! {
+    if json {
+        println!(
+            "{}",
+            Value::Object(vec![("error".to_string(), Value::String(err.to_string()))])
+        );
+    } else {
+        eprintln!("{err}");
+    }
+
+    process::exit(1);
+}
+
+/// Report the message `id` and exit, for failures nothing can recover from.
+fn fatal(id: &str, args: &[(&str, &dyn Display)]) -> ! {
+    eprintln!("{}", messages::text(id, args));
+
+    process::exit(1);
+}
+
+/// Exit for `word` resolving to nothing: quietly, as scripts testing the status expect, but
+/// with the same object `open resolve --json` prints under `--json`.
+fn nothing_found(word: &str, json: bool) -> ! {
+    if json {
+        let field = |key: &str, value: &str| (key.to_string(), Value::String(value.to_string()));
+
+        println!(
+            "{}",
+            Value::Object(vec![
+                field("arg", word),
+                field(
+                    "error",
+                    &messages::text("nothing-found", &[("word", &word)])
+                ),
+            ])
+        );
+    }
+
+    process::exit(1);
+}
+
+/// The new pull request form for the current branch, into the default branch.
+fn new_pr_url(cwd: &Path, remote: &str) -> Result<String, Error> {
+    let repo = GitRepository::from_path(cwd, remote)?;
+
+    let branch = repo
+        .branch
+        .clone()
+        .ok_or_else(|| Error::NotFound(messages::text("not-on-branch", &[])))?;
+
+    let fork = repo.pushed_to(&branch);
+    let pushed = fork.as_ref().unwrap_or(&repo);
+
+    if pushed.is_pushed(&branch) == Some(false) {
+        return Err(Error::NotFound(messages::text(
+            "push-first",
+            &[("branch", &branch), ("remote", &pushed.remote)],
+        )));
+    }
+
+    Ok(repo.new_pr_url(&repo.default_branch(), &branch, fork.as_ref()))
+}
+
+/// The page a repository flag like `--ci` or `--branch` asks for, if one was given. Branches
+/// and tags are checked to exist.
+fn flag_url(args: &CLI, cwd: &Path) -> Result<Option<String>, Error> {
+    if let Some(number) = args.issue {
+        return Ok(Some(
+            GitRepository::from_path(cwd, &args.remote)?.issue_url(number),
+        ));
+    }
+
+    if args.ci {
+        let repo = GitRepository::from_path(cwd, &args.remote)?;
+
+        let sha = repo
+            .resolve_commit("HEAD")
+            .ok_or_else(|| Error::NotFound(messages::text("no-commits", &[])))?;
+
+        let repo = repo.pushed_to(&repo.head_ref()).unwrap_or(repo);
+
+        return Ok(Some(ci::run_url(&repo, &sha)));
+    }
+
+    if args.new_pr {
+        return new_pr_url(cwd, &args.remote).map(Some);
+    }
+
+    if let Some(branch) = &args.branch {
+        let repo = GitRepository::from_path(cwd, &args.remote)?;
+
+        if !repo.is_branch(branch) {
+            return Err(Error::NotFound(messages::text(
+                "no-such-branch",
+                &[("branch", branch)],
+            )));
+        }
+
+        let repo = repo.pushed_to(branch).unwrap_or(repo);
+
+        repo.warn_if_unpushed(branch);
+
+        return Ok(Some(repo.tree_url(&args.pinned(&repo, branch), "")));
+    }
+
+    if let Some(tag) = &args.tag {
+        let repo = GitRepository::from_path(cwd, &args.remote)?;
+
+        if !repo.is_tag(tag) {
+            return Err(Error::NotFound(messages::text(
+                "no-such-tag",
+                &[("tag", tag)],
+            )));
+        }
+
+        return Ok(Some(repo.tree_url(&args.pinned(&repo, tag), "")));
+    }
+
+    Ok(None)
+}
+
+/// Work out what to open from the path arguments, passing `open` flags straight through.
+fn target(args: &CLI, cwd: &Path, ssh_tty: bool) -> Result<String, Error> {
+    if let Some(member) = &args.krate {
+        let repo = GitRepository::from_path(cwd, &args.remote)?;
+
+        let path = cargo::member_dir(cwd, member)
+            .and_then(|dir| repo.relative_path(&dir))
+            .ok_or_else(|| Error::NotFound(messages::text("no-such-crate", &[("name", member)])))?;
+
+        let rev = repo.head_ref();
+        let repo = repo.pushed_to(&rev).unwrap_or(repo);
+
+        repo.warn_if_unpushed(&rev);
+
+        return Ok(repo.tree_url(&args.pinned(&repo, &rev), &path));
+    }
+
+    if let Some(url) = flag_url(args, cwd)? {
+        return Ok(url);
+    }
+
+    if let Some(format) = args.archive {
+        let repo = GitRepository::from_path(cwd, &args.remote)?;
+
+        let rev = match args.path.first() {
+            None => repo.head_ref(),
+            Some(rev) => {
+                let sha = repo.resolve_commit(rev).ok_or_else(|| {
+                    Error::NotFound(messages::text("no-such-commit", &[("rev", rev)]))
+                })?;
+
+                // Keep branch and tag names readable, but don't trust short hashes to stay unique.
+                if is_sha(rev) {
+                    sha
+                } else {
+                    rev.clone()
+                }
+            }
+        };
+
+        let repo = repo.pushed_to(&rev).unwrap_or(repo);
+
+        repo.warn_if_unpushed(&rev);
+
+        return Ok(repo.archive_url(&rev, format));
+    }
+
+    if args.path.is_empty() {
+        if let Ok(file) = env::var("MO_FILE") {
+            return selection_url(Path::new(&file), &args.remote, args.force);
+        }
+    }
+
+    if args.path == ["-"] {
+        return stdin_commit_url(args, cwd);
+    }
+
+    let current_dir = cwd.to_string_lossy().to_string();
+
+    let remote_path = if args.path.is_empty() {
+        match GitRepository::from_path(cwd, &args.remote) {
+            Ok(repo) => repo.http_url(),
+            // A remote on this machine opens as a directory, with no web page to show.
+            Err(Error::LocalRemote(_, path)) => path.to_string_lossy().to_string(),
+            Err(_) => current_dir,
+        }
+    } else {
+        match args.path.join(" ") {
+            path if path == "." => current_dir,
+            path => path,
+        }
+    };
+
+    if remote_path.starts_with('-') {
+        pass_through(&args.path);
+    }
+
+    match resolution(args, &remote_path, cwd)? {
+        Some(Resolution {
+            kind: Kind::Commit | Kind::PullRequest,
+            target,
+        }) if args.change().is_some() => {
+            return args.change_as(&GitRepository::from_url(cwd, &target)?, target)
+        }
+        // Issues have no patch or diff to show, and a plugin's URL is its own.
+        Some(Resolution {
+            kind:
+                Kind::Commit
+                | Kind::PullRequest
+                | Kind::Blob
+                | Kind::Issue
+                | Kind::Range
+                | Kind::Tag
+                | Kind::Branch
+                | Kind::Repository
+                | Kind::Plugin,
+            target,
+        }) => return Ok(target),
+        _ => {}
+    }
+
+    path_target(args, cwd, remote_path, ssh_tty)
+}
+
+/// A path argument's target: its page on the web when it's tracked, or the file itself, or the
+/// editor's link to it with `--editor`.
+fn path_target(
+    args: &CLI,
+    cwd: &Path,
+    remote_path: String,
+    ssh_tty: bool,
+) -> Result<String, Error> {
+    let location = Location::parse(&remote_path);
+
+    // Rather than hand the launcher a path it can't find either.
+    if location.is_none()
+        && Shorthand::parse(&remote_path).is_some_and(|shorthand| shorthand.is_path_like(cwd))
+    {
+        return Err(Error::NotFound(messages::text(
+            "no-such-file",
+            &[("path", &remote_path)],
+        )));
+    }
+
+    if args.editor.is_none() && !args.local {
+        if let Some(url) = location
+            .as_ref()
+            .and_then(|location| tracked_file_url(location, args))
+        {
+            return Ok(url);
+        }
+    }
+
+    Ok(match args.editor {
+        Some(editor) if !is_url(&remote_path) => {
+            location.map_or(remote_path, |location| editor.url(&location, ssh_tty))
+        }
+        _ => remote_path,
+    })
+}
+
+/// Hand flags straight to the launcher, e.g. `open -a Safari`, and exit with its stderr on stdout.
+fn pass_through(args: &[String]) -> ! {
+    let command = if args.join(" ") == "--help" {
+        vec!["-h"]
+    } else {
+        args.iter().map(String::as_str).collect()
+    };
+
+    let output = opener::launcher()
+        .args(command)
+        .stderr(Stdio::inherit())
+        .output()
+        .unwrap_or_else(|error| fatal("run-failed", &[("error", &error)]));
+
+    stdout()
+        .write_all(&output.stderr)
+        .unwrap_or_else(|error| fatal("stdout-failed", &[("error", &error)]));
+
+    process::exit(0);
+}
+
+/// The commit URL for the first entry of `git blame` (plain or `--porcelain`) or `git log`
+/// output piped in, e.g. `git blame -L10,10 file | open -`.
+fn stdin_commit_url(args: &CLI, cwd: &Path) -> Result<String, Error> {
+    let mut line = String::new();
+
+    io::stdin()
+        .read_line(&mut line)
+        .map_err(|err| Error::Io(PathBuf::from("<stdin>"), err))?;
+
+    // Blame marks boundary commits with `^`, and `git log` leads with `commit `.
+    let sha = line
+        .trim_start_matches("commit ")
+        .split_whitespace()
+        .next()
+        .map(|sha| sha.trim_start_matches('^'))
+        .filter(|sha| is_sha(sha))
+        .ok_or_else(|| Error::NotFound(messages::text("no-commit-on-stdin", &[])))?;
+
+    if sha.chars().all(|c| c == '0') {
+        return Err(Error::NotFound(messages::text("not-committed", &[])));
+    }
+
+    let repo = GitRepository::from_path(cwd, &args.remote)?;
+
+    let sha = repo
+        .resolve_commit(sha)
+        .ok_or_else(|| Error::NotFound(messages::text("no-such-commit", &[("rev", &sha)])))?;
+
+    args.change_as(&repo, repo.commit_url(&sha))
+}
+
+/// A permalink for the selection an editor plugin describes with `MO_FILE`, `MO_LINE_START`,
+/// `MO_LINE_END` and `MO_SHA`, so plugins needn't construct arguments.
+fn selection_url(file: &Path, remote: &str, force: bool) -> Result<String, Error> {
+    let repo = GitRepository::containing(file, remote)?;
+
+    let path = repo.relative_path(file).ok_or_else(|| {
+        Error::NotFound(messages::text(
+            "not-in-repository",
+            &[("path", &file.display())],
+        ))
+    })?;
+
+    let rev = env::var("MO_SHA").unwrap_or_else(|_| "HEAD".to_string());
+
+    let sha = repo
+        .resolve_commit(&rev)
+        .ok_or_else(|| Error::NotFound(messages::text("no-such-commit", &[("rev", &rev)])))?;
+
+    if !force && repo.is_modified(&path) {
+        eprintln!(
+            "{}",
+            messages::text("uncommitted-changes", &[("path", &path)])
+        );
+    }
+
+    let line = |name| env::var(name).ok().and_then(|line| line.parse().ok());
+
+    Ok(repo.blob_url(&sha, &path) + &repo.line_anchor(line("MO_LINE_START"), line("MO_LINE_END")))
+}
+
+/// The web page of a file tracked in a repository, on the current branch and at its line or lines
+/// if given, or `None` for a file to open locally.
+fn tracked_file_url(location: &Location, args: &CLI) -> Option<String> {
+    if !location.path.is_file() {
+        return None;
+    }
+
+    let repo = GitRepository::containing(&location.path, &args.remote).ok()?;
+
+    let path = repo
+        .relative_path(&location.path)
+        .filter(|path| repo.is_tracked(path))?;
+
+    if !args.force && repo.is_modified(&path) {
+        eprintln!(
+            "{}",
+            messages::text("uncommitted-changes", &[("path", &path)])
+        );
+    }
+
+    let rev = repo.head_ref();
+    let repo = repo.pushed_to(&rev).unwrap_or(repo);
+
+    repo.warn_if_unpushed(&rev);
+
+    let rev = args.pinned(&repo, &rev);
+
+    if args.raw {
+        return Some(repo.raw_url(&rev, &path));
+    }
+
+    if args.history {
+        return Some(repo.history_url(&rev, &path));
+    }
+
+    Some(repo.blob_url(&rev, &path) + &repo.line_anchor(location.line, location.end_line))
+}
+
+/// Print, relay back over SSH, or locally open URLs or paths, returning false if the user
+/// declined to.
+fn open(targets: &[String], print: Option<Print>, ssh_tty: bool, yes: bool) -> bool {
+    let targets: Vec<String> = targets
+        .iter()
+        .map(|target| {
+            if is_url(target) || !ssh_tty {
+                return target.clone();
+            }
+
+            let client_home =
+                env::var("SSH_CLIENT_HOME").unwrap_or_else(|_| fatal("no-client-home", &[]));
+
+            PathMapper::from_config(Path::new("."), &client_home).map(&expand_tilde(target))
+        })
+        .collect();
+
+    // A CI job has no browser to open, so links go to its log instead.
+    if let Some(print) = print.or_else(|| ci::environment().map(|_| Print::Lines)) {
+        for target in &targets {
+            print.target(target);
+        }
+    } else if !yes && !confirm::confirmed(&targets, ssh_tty) {
+        return false;
+    } else if ssh_tty {
+        // One connection carries the whole batch, a line per target.
+        if let Ok(mut stream) = TcpStream::connect((LOCALHOST, PORT)) {
+            stream
+                .write_all(targets.join("\n").as_bytes())
+                .unwrap_or_else(|error| fatal("relay-failed", &[("error", &error)]));
+        } else {
+            // Without the port forwarded, the terminal can still hand the client a link to click,
+            // and the clipboard the URLs to paste.
+            let copied =
+                osc52::supported(Path::new(".")) && osc52::copy(&targets.join("\n")).is_ok();
+
+            eprintln!(
+                "{}",
+                messages::text(
+                    if copied {
+                        "no-relay-copied"
+                    } else {
+                        "no-relay"
+                    },
+                    &[("port", &PORT)]
+                )
+            );
+
+            // Show the URLs themselves, so they can still be selected and pasted where clicking
+            // through the link isn't supported.
+            for target in &targets {
+                if is_url(target) {
+                    println!("{}", hyperlink::link(target, target));
+                } else {
+                    println!("{target}");
+                }
+            }
+        }
+    } else {
+        for target in &targets {
+            opener::open(target).unwrap_or_else(|error| {
+                fatal("open-failed", &[("target", target), ("error", &error)])
+            });
+        }
+    }
+
+    true
+}
+
+fn main() {
+    let cwd =
+        env::current_dir().unwrap_or_else(|error| fatal("no-current-dir", &[("error", &error)]));
+
+    let ssh_tty = env::var_os("SSH_TTY").is_some();
+
+    network::set_offline(config::get_bool(&cwd, "offline"));
+
+    if let Some(timeout) = config::get(&cwd, "timeout").and_then(|timeout| timeout.parse().ok()) {
+        watchdog::set_timeout(timeout);
+    }
+
+    if git_open::invoked() {
+        let args = GitOpen::parse();
+
+        match args.url(&cwd) {
+            Ok(url) => {
+                if !open(&[url], args.print.then_some(Print::Lines), ssh_tty, false) {
+                    process::exit(1);
+                }
+            }
+            Err(err) => fail(&err, false),
+        }
+
+        return;
+    }
+
+    let args = CLI::parse_from(arguments(env::args_os(), Path::exists));
+
+    if args.offline {
+        network::set_offline(true);
+    }
+
+    if let Some(provider) = &args.provider {
+        provider::set_override(provider.clone());
+    }
+
+    if args.refresh {
+        if let Ok(repo) = GitRepository::from_path(&cwd, &args.remote) {
+            repo.forget_default_branch();
+        }
+    }
+
+    let print = args.print().unwrap_or(Print::Lines);
+
+    if let Some(word) = &args.resolve_only {
+        match resolve::resolve(word, &cwd, &args.remote) {
+            Some(resolution) if args.json => {
+                print
+                    .emit(&Value::Object(description(&resolution, &cwd, &args.remote)).to_string());
+            }
+            Some(resolution) => print.emit(&resolution.target),
+            None => nothing_found(word, args.json),
+        }
+
+        return;
+    }
+
+    if let Some(form) = args.url_form {
+        let repo = match args.path.first() {
+            Some(url) => GitRepository::from_url(&cwd, url),
+            None => GitRepository::from_path(&cwd, &args.remote),
+        };
+
+        match repo.and_then(|repo| repo.url_as(form)) {
+            Ok(url) => print.emit(&url),
+            Err(err) => fail(&err, args.json),
+        }
+
+        return;
+    }
+
+    if args.clone {
+        match clone(&cwd, args.path.first(), args.path.get(1).map(Path::new)) {
+            Ok(path) => print.emit(&path.to_string_lossy()),
+            Err(err) => fail(&err, args.json),
+        }
+
+        return;
+    }
+
+    if args.all && args.command.is_none() {
+        let word = args.path.join(" ");
+        let candidates = resolve::candidates(&word, &cwd, &args.remote);
+
+        if candidates.is_empty() {
+            nothing_found(&word, args.json);
+        }
+
+        for candidate in candidates {
+            print.emit(&if args.json {
+                Value::Object(description(&candidate, &cwd, &args.remote)).to_string()
+            } else {
+                format!("{}\t{}", candidate.kind.label(), candidate.target)
+            });
+        }
+
+        return;
+    }
+
+    let targets = match &args.command {
+        Some(command) => run(command, &args, &cwd, ssh_tty),
+        None => target(&args, &cwd, ssh_tty).map(|target| vec![target]),
+    }
+    .unwrap_or_else(|err| fail(&err, args.json));
+
+    if print_as_asked(&args, &cwd, &targets).unwrap_or_else(|err| fail(&err, args.json)) {
+        return;
+    }
+
+    if !open(&targets, args.print(), ssh_tty, args.yes) {
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+
+    use clap::Parser;
+
+    use super::{arguments, Commands, CLI};
+
+    fn parse(args: &[&str], exists: bool) -> CLI {
+        CLI::try_parse_from(arguments(args.iter().map(OsString::from), |_| exists))
+            .expect("valid arguments")
+    }
+
+    #[test]
+    fn prefers_an_existing_path_to_a_subcommand() {
+        for name in [
+            "packages", "daemon", "deps", "watch", "links", "license", "security",
+        ] {
+            let args = parse(&["open", name], true);
+
+            assert!(args.command.is_none(), "{name}");
+            assert_eq!(args.path, [name]);
+        }
+
+        let args = parse(&["open", "-p", "packages"], true);
+
+        assert!(args.print);
+        assert_eq!(args.path, ["packages"]);
+    }
+
+    #[test]
+    fn runs_subcommands_without_such_a_path() {
+        assert!(matches!(
+            parse(&["open", "packages"], false).command,
+            Some(Commands::Packages { .. })
+        ));
+        assert!(matches!(
+            parse(&["open", "daemon"], false).command,
+            Some(Commands::Daemon)
+        ));
+    }
+
+    #[test]
+    fn leaves_other_words_alone() {
+        let args = arguments(["open", "README.md"].map(OsString::from), |_| true);
 
-        Command::new(OPEN)
-            .args(&args)
-            .spawn()
-            .expect("Failed to open URL");
+        assert_eq!(args, ["open", "README.md"]);
     }
 }
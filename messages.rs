@@ -0,0 +1,178 @@
+use std::env;
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::config;
+
+type Table = &'static [(&'static str, &'static str)];
+
+/// English, the default, and the fallback for anything missing from another locale.
+const EN: Table = &[
+    (
+        "bad-provider",
+        "expected github, gitlab, bitbucket, gitea, azure, codecommit, sourcehut, gerrit or custom:<template-set>, got {provider}",
+    ),
+    ("bad-remote-url", "Unable to parse remote URL: {url}"),
+    ("clone-needs-url", "--clone needs a repository URL to clone"),
+    ("confirm-many", "About to open {count} targets."),
+    (
+        "confirm-needs-yes",
+        "Not opened: there's no terminal to confirm on, so pass --yes.",
+    ),
+    ("confirm-prompt", "Open anyway?"),
+    (
+        "confirm-relay",
+        "About to relay from {host}, which isn't one of magic-opener.relay-hosts.",
+    ),
+    ("confirm-scheme", "{target} isn't an https link."),
+    ("daemon-bad-payload", "Ignoring a relayed batch: {error}"),
+    ("daemon-failed", "Failed to open {target}: {error}"),
+    (
+        "daemon-listening",
+        "Listening on localhost:{port} for targets relayed over SSH",
+    ),
+    ("daemon-refused", "Not opening {target}: not a URL or path"),
+    (
+        "daemon-refused-path",
+        "Not opening {target}: not a file or directory under a magic-opener.mount here",
+    ),
+    (
+        "daemon-refused-runnable",
+        "Not opening {target}: only directories and documents are opened",
+    ),
+    (
+        "daemon-refused-scheme",
+        "Not opening {target}: add its scheme to magic-opener.daemon-schemes to allow it",
+    ),
+    ("daemon-too-large", "more than {limit} bytes"),
+    ("git-failed", "`git {command}` failed: {stderr}"),
+    (
+        "git-timeout",
+        "`git {command}` hung for {seconds}s and was killed",
+    ),
+    (
+        "local-remote",
+        "{remote} is a repository on this machine, at {path}",
+    ),
+    ("no-change-format", "{provider} serves no {format} for this"),
+    ("no-ci-config", "No CI configuration found"),
+    (
+        "no-client-home",
+        "No $SSH_CLIENT_HOME set! It must be set in the SSH client config.",
+    ),
+    ("no-clipboard", "No clipboard tool found, e.g. wl-copy, xclip or xsel"),
+    ("no-code-owners", "No code owners for {path}"),
+    ("no-commit-on-stdin", "No commit found on stdin"),
+    ("no-commits", "No commits yet"),
+    ("no-containing-tag", "No tag contains {rev} yet"),
+    ("no-current-dir", "Failed to get the current directory: {error}"),
+    ("no-license", "No license found"),
+    ("no-links", "No trailers in {rev} link anywhere"),
+    ("no-merging-pr", "No pull request found that merged {rev}"),
+    (
+        "no-orig-head",
+        "No ORIG_HEAD: nothing was rebased or merged",
+    ),
+    (
+        "no-pr-refs",
+        "{provider} doesn't publish pull request refs to fetch",
+    ),
+    (
+        "no-relay",
+        "Nothing is listening on localhost:{port} to open these here, so click instead:",
+    ),
+    (
+        "no-relay-copied",
+        "Nothing is listening on localhost:{port} to open these here, so they're on your clipboard, or click:",
+    ),
+    ("no-section", "{provider} has no {section} page"),
+    ("no-security-policy", "No security policy found"),
+    ("no-submodules", "No submodules in this repository"),
+    ("no-such-branch", "No such branch: {branch}"),
+    ("no-such-change", "No such change: {number}"),
+    ("no-such-choice", "No such choice: {choice}"),
+    ("no-such-commit", "No such commit: {rev}"),
+    ("no-such-crate", "No crate {name} in this workspace"),
+    ("no-such-file", "No such file: {path}"),
+    ("no-such-format", "No such format: {format}"),
+    ("no-such-remote", "No such remote: {remote}"),
+    ("no-such-tag", "No such tag: {tag}"),
+    ("no-upstream", "{branch} has no upstream branch"),
+    ("not-committed", "Not committed yet"),
+    ("not-in-repository", "{path} is not in this repository"),
+    ("not-on-branch", "Not on a branch"),
+    (
+        "not-pushed",
+        "Warning: {rev} isn't on {remote} yet; push it with `git push {remote} {rev}`.",
+    ),
+    ("nothing-found", "Nothing found for {word}"),
+    ("nothing-to-choose", "Nothing to choose from"),
+    ("offline", "Offline, not running {action}"),
+    ("open-failed", "Failed to open {target}: {error}"),
+    ("pick-reading", "Open {arg} as"),
+    ("pick-submodule", "Open submodule"),
+    (
+        "push-first",
+        "{branch} isn't on {remote} yet; push it with `git push -u {remote} {branch}` first",
+    ),
+    (
+        "qr-too-wide",
+        "The QR code won't fit in {columns} columns; widen the terminal or shrink its font",
+    ),
+    (
+        "relay-failed",
+        "Couldn't write the targets to the relay socket: {error}",
+    ),
+    ("run-failed", "Failed to run the launcher: {error}"),
+    ("stdout-failed", "Failed to write to stdout: {error}"),
+    (
+        "uncommitted-changes",
+        "Warning: {path} has uncommitted changes, so the web version differs.",
+    ),
+];
+
+/// Message tables by locale. Packagers add translations here, keyed by the same message IDs.
+const LOCALES: [(&str, Table); 1] = [("en", EN)];
+
+/// The message `id` in the selected locale, with each `{name}` filled in from `args`.
+///
+/// `$MAGIC_OPENER_LOCALE`, then `magic-opener.locale`, picks the locale, by its full name or
+/// its language, so `de_CH.UTF-8` finds `de`.
+pub fn text(id: &str, args: &[(&str, &dyn Display)]) -> String {
+    let template = lookup(table(), id).or_else(|| lookup(EN, id)).unwrap_or(id);
+
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), &value.to_string())
+        })
+}
+
+fn lookup(table: Table, id: &str) -> Option<&'static str> {
+    table
+        .iter()
+        .find_map(|(key, text)| (*key == id).then_some(*text))
+}
+
+fn table() -> Table {
+    static TABLE: OnceLock<Table> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        let locale = env::var("MAGIC_OPENER_LOCALE")
+            .ok()
+            .or_else(|| config::get(Path::new("."), "locale"))
+            .unwrap_or_default();
+
+        let name = locale.split('.').next().unwrap_or_default();
+        let language = name.split(['_', '-']).next().unwrap_or_default();
+
+        [name, language]
+            .iter()
+            .find_map(|wanted| {
+                LOCALES
+                    .iter()
+                    .find_map(|(locale, table)| (locale == wanted).then_some(*table))
+            })
+            .unwrap_or(EN)
+    })
+}
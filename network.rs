@@ -0,0 +1,23 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::repo::Error;
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Forbid everything that would reach the network for the rest of this run.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Fail with [`Error::Offline`] if `action` isn't allowed to use the network.
+pub fn check(action: &str) -> Result<(), Error> {
+    if is_offline() {
+        Err(Error::Offline(action.to_string()))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+use std::env;
+#[cfg(not(windows))]
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::config;
+use crate::resolve::is_url;
+
+/// The platform's launcher for URLs and files, which also takes `open`'s own flags on macOS.
+#[cfg(target_os = "macos")]
+const PROGRAM: &str = "/usr/bin/open";
+#[cfg(windows)]
+const PROGRAM: &str = "explorer.exe";
+#[cfg(not(any(target_os = "macos", windows)))]
+const PROGRAM: &str = "xdg-open";
+
+/// Open a URL in the default browser, or a file or directory in its default application.
+///
+/// `$MAGIC_OPENER_CMD` or `magic-opener.opener` replaces the platform's launcher, e.g. with
+/// `firefox` or a wrapper script, which gets the target as its last argument. Unlike the
+/// platform's launchers, which hand off and exit, that may run until the browser closes, so
+/// it's left running and reaped in the background rather than waited for.
+pub fn open(target: &str) -> io::Result<()> {
+    let Some(mut command) = custom() else {
+        return command(target).status().map(drop);
+    };
+
+    let mut child = command.arg(target).stdin(Stdio::null()).spawn()?;
+
+    thread::spawn(move || child.wait());
+
+    Ok(())
+}
+
+/// The launcher, without a target, for passing flags straight through.
+pub fn launcher() -> Command {
+    custom().unwrap_or_else(|| Command::new(PROGRAM))
+}
+
+/// The configured launcher, split on whitespace into the program and its leading arguments.
+fn custom() -> Option<Command> {
+    let line = env::var("MAGIC_OPENER_CMD")
+        .ok()
+        .or_else(|| config::get(Path::new("."), "opener"))?;
+
+    let mut words = line.split_whitespace();
+
+    let mut command = Command::new(words.next()?);
+    command.args(words);
+
+    Some(command)
+}
+
+#[cfg(windows)]
+fn command(target: &str) -> Command {
+    if !is_url(target) {
+        let mut command = Command::new(PROGRAM);
+        command.arg(target);
+        return command;
+    }
+
+    // Hand URLs to their protocol handler directly rather than through `cmd /C start`, which
+    // would run whatever a `"` in the URL let out of its quotes.
+    let mut command = Command::new("rundll32.exe");
+    command.args(["url.dll,FileProtocolHandler", target]);
+    command
+}
+
+#[cfg(not(windows))]
+fn command(target: &str) -> Command {
+    if is_wsl() {
+        return wsl_command(target);
+    }
+
+    let mut command = Command::new(PROGRAM);
+
+    // Leave the terminal focused while the browser loads.
+    if cfg!(target_os = "macos") && is_url(target) {
+        command.arg("--background");
+    }
+
+    command.arg(target);
+    command
+}
+
+/// Whether this is Linux under WSL, where `xdg-open` can't reach the Windows desktop.
+#[cfg(not(windows))]
+pub fn is_wsl() -> bool {
+    env::var_os("WSL_DISTRO_NAME").is_some()
+        || fs::read_to_string("/proc/version")
+            .is_ok_and(|version| version.to_lowercase().contains("microsoft"))
+}
+
+/// Hand the target to Windows: `wslview` from wslu if it's installed, otherwise `explorer.exe`
+/// with local paths translated to Windows ones.
+#[cfg(not(windows))]
+fn wsl_command(target: &str) -> Command {
+    let wslview = env::var_os("PATH")
+        .is_some_and(|path| env::split_paths(&path).any(|dir| dir.join("wslview").is_file()));
+
+    let mut command = Command::new(if wslview { "wslview" } else { "explorer.exe" });
+
+    if is_url(target) || wslview {
+        command.arg(target);
+    } else {
+        command.arg(windows_path(target));
+    }
+
+    command
+}
+
+/// `/mnt/c/Users` as `C:\Users`, and anything else inside the distribution as a
+/// `\\wsl.localhost\<distro>` share path.
+#[cfg(not(windows))]
+fn windows_path(target: &str) -> String {
+    let path = fs::canonicalize(target).map_or_else(
+        |_| target.to_string(),
+        |path| path.to_string_lossy().to_string(),
+    );
+
+    let drive = path.strip_prefix("/mnt/").and_then(|rest| {
+        let (drive, rest) = rest.split_once('/').unwrap_or((rest, ""));
+
+        (drive.len() == 1 && drive.chars().all(|c| c.is_ascii_alphabetic()))
+            .then(|| format!("{}:\\{}", drive.to_uppercase(), rest.replace('/', "\\")))
+    });
+
+    drive.unwrap_or_else(|| match env::var("WSL_DISTRO_NAME") {
+        Ok(distro) if path.starts_with('/') => {
+            format!("\\\\wsl.localhost\\{distro}{}", path.replace('/', "\\"))
+        }
+        _ => path,
+    })
+}
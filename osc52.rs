@@ -0,0 +1,107 @@
+use std::env;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::config;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// How much of a sequence screen passes on in one DCS string; it truncates longer ones.
+const SCREEN_CHUNK: usize = 76;
+
+/// Terminals that don't understand OSC 52, by `$TERM` prefix.
+const UNSUPPORTED: [&str; 3] = ["dumb", "linux", "vt"];
+
+/// Whether the terminal is likely to honour OSC 52. Terminals rarely say whether they do, and
+/// unhandled escapes are dropped anyway, so any but the console and the most basic are tried.
+/// `magic-opener.osc52` settles it either way.
+pub fn supported(dir: &Path) -> bool {
+    if let Some(setting) = config::get(dir, "osc52") {
+        return matches!(setting.as_str(), "true" | "yes" | "on" | "1");
+    }
+
+    env::var("TERM").is_ok_and(|term| !UNSUPPORTED.iter().any(|prefix| term.starts_with(prefix)))
+}
+
+/// Put `text` on the clipboard of whatever terminal the user is at, however many SSH hops
+/// away, by writing an OSC 52 escape to the controlling terminal.
+pub fn copy(text: &str) -> io::Result<()> {
+    let sequence = wrap(&format!("\x1b]52;c;{}\x07", encode(text.as_bytes())));
+
+    // Stdout may be piped, but the controlling terminal is the one displaying.
+    match OpenOptions::new().write(true).open("/dev/tty") {
+        Ok(mut tty) => tty.write_all(sequence.as_bytes()),
+        Err(_) => io::stderr().write_all(sequence.as_bytes()),
+    }
+}
+
+/// `sequence` wrapped for a multiplexer in between to pass on to the terminal outside: tmux
+/// wants it in one DCS passthrough with escapes doubled, screen in short DCS chunks.
+fn wrap(sequence: &str) -> String {
+    if env::var_os("TMUX").is_some() {
+        return format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"));
+    }
+
+    if env::var_os("STY").is_some() {
+        let mut wrapped = String::new();
+
+        for chunk in sequence.as_bytes().chunks(SCREEN_CHUNK) {
+            wrapped.push_str("\x1bP");
+            wrapped.push_str(&String::from_utf8_lossy(chunk));
+            wrapped.push_str("\x1b\\");
+        }
+
+        return wrapped;
+    }
+
+    sequence.to_string()
+}
+
+/// Standard, padded base64, which is all OSC 52 takes.
+fn encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | u32::from(*byte) << (16 - 8 * index)
+        });
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(char::from(
+                    BASE64[(group >> (18 - 6 * index)) as usize & 63],
+                ));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode;
+
+    #[test]
+    fn encodes_rfc_4648_vectors() {
+        for (input, expected) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            assert_eq!(encode(input.as_bytes()), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn encodes_every_byte() {
+        assert_eq!(encode(&[0x00, 0xff, 0xfe]), "AP/+");
+    }
+}
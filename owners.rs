@@ -0,0 +1,146 @@
+use std::fs;
+
+use crate::glob;
+use crate::repo::GitRepository;
+
+/// Where GitHub and GitLab look for a CODEOWNERS file, in order.
+const LOCATIONS: [&str; 4] = [
+    ".github/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+];
+
+/// The owners of `path`, relative to the repository root.
+pub fn owners(repo: &GitRepository, path: &str) -> Option<Vec<String>> {
+    let contents = LOCATIONS
+        .iter()
+        .find_map(|location| fs::read_to_string(repo.root.join(location)).ok())?;
+
+    owners_in(&contents, path)
+}
+
+/// The owners the CODEOWNERS `contents` give `path`. The last matching rule wins, so a rule
+/// without owners leaves a path unowned.
+fn owners_in(contents: &str, path: &str) -> Option<Vec<String>> {
+    contents
+        .lines()
+        .rev()
+        .map(str::trim)
+        // Skip comments, and GitLab's `[Section]` headers.
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('['))
+        .find_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pattern = fields.next()?;
+
+            matches(pattern, path).then(|| fields.map(str::to_string).collect::<Vec<_>>())
+        })
+        .filter(|owners| !owners.is_empty())
+}
+
+/// CODEOWNERS patterns follow `.gitignore` rules: a leading or inner slash anchors the pattern
+/// to the root, otherwise it matches at any depth, and matching a directory covers its contents.
+fn matches(pattern: &str, path: &str) -> bool {
+    let directory = pattern.ends_with('/');
+    let trimmed = pattern.trim_start_matches('/').trim_end_matches('/');
+
+    let glob = if pattern.starts_with('/') || trimmed.contains('/') {
+        trimmed.to_string()
+    } else {
+        format!("**/{trimmed}")
+    };
+
+    // `docs/*` only covers the files directly inside `docs`, and `*/**` needs at least one
+    // segment so that `build/` leaves a file named `build` alone.
+    let contents = !glob.ends_with("/*") && glob::matches(&format!("{glob}/*/**"), path);
+
+    contents || (!directory && glob::matches(&glob, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{matches, owners_in};
+
+    #[test]
+    fn matches_unanchored_patterns_at_any_depth() {
+        for (pattern, path, expected) in [
+            ("*.rs", "main.rs", true),
+            ("*.rs", "src/deep/lib.rs", true),
+            ("*.rs", "main.rsx", false),
+            ("docs", "docs/index.md", true),
+            ("docs", "src/docs/index.md", true),
+            ("docs", "docs", true),
+            ("README.md", "sub/README.md", true),
+        ] {
+            assert_eq!(matches(pattern, path), expected, "{pattern} {path}");
+        }
+    }
+
+    #[test]
+    fn anchors_patterns_with_a_slash() {
+        for (pattern, path, expected) in [
+            ("/docs", "docs/index.md", true),
+            ("/docs", "src/docs/index.md", false),
+            ("src/lib", "src/lib/a.rs", true),
+            ("src/lib", "vendor/src/lib/a.rs", false),
+            ("/main.rs", "main.rs", true),
+            ("/main.rs", "src/main.rs", false),
+        ] {
+            assert_eq!(matches(pattern, path), expected, "{pattern} {path}");
+        }
+    }
+
+    #[test]
+    fn matches_directories_with_a_trailing_slash() {
+        for (pattern, path, expected) in [
+            ("build/", "build/out.txt", true),
+            ("build/", "src/build/out.txt", true),
+            ("build/", "build", false),
+            ("/docs/*", "docs/index.md", true),
+            ("/docs/*", "docs/api/index.md", false),
+        ] {
+            assert_eq!(matches(pattern, path), expected, "{pattern} {path}");
+        }
+    }
+
+    #[test]
+    fn matches_double_stars() {
+        for (pattern, path, expected) in [
+            ("**/logs", "logs/a.log", true),
+            ("**/logs", "deep/down/logs/a.log", true),
+            ("/src/**/test.rs", "src/test.rs", true),
+            ("/src/**/test.rs", "src/a/b/test.rs", true),
+            ("/src/**/test.rs", "lib/a/test.rs", false),
+            ("/apps/**", "apps/web/index.ts", true),
+        ] {
+            assert_eq!(matches(pattern, path), expected, "{pattern} {path}");
+        }
+    }
+
+    #[test]
+    fn lets_the_last_match_win() {
+        let contents = "\
+# Everything, unless a later rule says otherwise.
+*       @org/everyone
+
+[Docs]
+/docs/  @org/writers @lead
+/docs/generated/
+
+*.rs    @rustacean
+";
+
+        let owners = |path| owners_in(contents, path);
+
+        assert_eq!(owners("README.md"), Some(vec!["@org/everyone".to_string()]));
+        assert_eq!(
+            owners("docs/guide.md"),
+            Some(vec!["@org/writers".to_string(), "@lead".to_string()])
+        );
+        assert_eq!(owners("docs/generated/api.md"), None);
+        assert_eq!(
+            owners("docs/example.rs"),
+            Some(vec!["@rustacean".to_string()])
+        );
+    }
+}
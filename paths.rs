@@ -0,0 +1,188 @@
+use std::fs;
+use std::path::Path;
+
+use shellexpand::tilde;
+
+use crate::config;
+
+/// The mount used when no `magic-opener.mount` rules are configured.
+const DEFAULT_MOUNT: (&str, &str) = ("/bits", "~/Mounts/bits");
+
+/// Expand a leading `~` to the local home directory.
+pub fn expand_tilde(path: &str) -> String {
+    tilde(path).into_owned()
+}
+
+/// Translates paths on this host to where the SSH client mounts them, and back.
+///
+/// Rules are `(local, client)` prefix pairs. The longest matching prefix wins, and only whole
+/// path components match, so `/bits` covers `/bits/src` but not `/bitsy`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathMapper {
+    rules: Vec<(String, String)>,
+}
+
+impl PathMapper {
+    pub fn new(rules: Vec<(String, String)>) -> Self {
+        Self { rules }
+    }
+
+    /// Rules from each `magic-opener.mount` value, `<local>=<client>`, in config order. A `~`
+    /// on the client side stands for the client's home directory, `client_home`.
+    pub fn from_config(dir: &Path, client_home: &str) -> Self {
+        let mounts = config::get_all(dir, "mount");
+
+        let rules: Vec<(String, String)> = if mounts.is_empty() {
+            vec![(DEFAULT_MOUNT.0.to_string(), DEFAULT_MOUNT.1.to_string())]
+        } else {
+            mounts
+                .iter()
+                .filter_map(|mount| mount.split_once('='))
+                .map(|(local, client)| (local.to_string(), client.to_string()))
+                .collect()
+        };
+
+        Self::new(
+            rules
+                .into_iter()
+                .map(|(local, client)| {
+                    let client = match client.strip_prefix('~') {
+                        Some(rest) => format!("{client_home}{rest}"),
+                        None => client,
+                    };
+
+                    (expand_tilde(&local), client)
+                })
+                .collect(),
+        )
+    }
+
+    /// Where the client sees a local path. Paths outside every rule are unchanged.
+    pub fn map(&self, path: &str) -> String {
+        Self::translate(
+            self.rules
+                .iter()
+                .map(|(local, client)| (local.as_str(), client.as_str())),
+            path,
+        )
+    }
+
+    /// The local path for one the client sees: the inverse of [`map`](Self::map), for the end of
+    /// the relay that receives client paths.
+    pub fn unmap(&self, path: &str) -> String {
+        Self::translate(
+            self.rules
+                .iter()
+                .map(|(local, client)| (client.as_str(), local.as_str())),
+            path,
+        )
+    }
+
+    /// The same rules with each client side that exists canonicalized, to hold canonical paths
+    /// up against.
+    pub fn canonicalized(&self) -> Self {
+        Self::new(
+            self.rules
+                .iter()
+                .map(|(local, client)| {
+                    let client = fs::canonicalize(client).map_or_else(
+                        |_| client.clone(),
+                        |client| client.to_string_lossy().to_string(),
+                    );
+
+                    (local.clone(), client)
+                })
+                .collect(),
+        )
+    }
+
+    fn translate<'a>(rules: impl Iterator<Item = (&'a str, &'a str)>, path: &str) -> String {
+        rules
+            .filter_map(|(from, to)| {
+                let from = from.trim_end_matches('/');
+                let rest = path.strip_prefix(from)?;
+
+                (rest.is_empty() || rest.starts_with('/')).then_some((from.len(), to, rest))
+            })
+            .max_by_key(|(length, ..)| *length)
+            .map_or_else(
+                || path.to_string(),
+                |(_, to, rest)| format!("{}{rest}", to.trim_end_matches('/')),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PathMapper;
+
+    fn mapper(rules: &[(&str, &str)]) -> PathMapper {
+        PathMapper::new(
+            rules
+                .iter()
+                .map(|(local, client)| ((*local).to_string(), (*client).to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn maps_and_unmaps() {
+        let mounts = mapper(&[("/bits", "/Users/me/Mounts/bits")]);
+
+        assert_eq!(
+            mounts.map("/bits/src/main.rs"),
+            "/Users/me/Mounts/bits/src/main.rs"
+        );
+        assert_eq!(
+            mounts.unmap("/Users/me/Mounts/bits/src/main.rs"),
+            "/bits/src/main.rs"
+        );
+        assert_eq!(mounts.map("/bits"), "/Users/me/Mounts/bits");
+        assert_eq!(mounts.unmap("/Users/me/Mounts/bits"), "/bits");
+    }
+
+    #[test]
+    fn round_trips() {
+        let mounts = mapper(&[("/bits/", "/Volumes/bits/"), ("/home/me", "/Volumes/home")]);
+
+        for path in [
+            "/bits",
+            "/bits/a/b.txt",
+            "/home/me/notes.md",
+            "/elsewhere/x",
+        ] {
+            assert_eq!(mounts.unmap(&mounts.map(path)), path);
+        }
+    }
+
+    #[test]
+    fn leaves_paths_outside_every_rule() {
+        let mounts = mapper(&[("/bits", "/Volumes/bits")]);
+
+        assert_eq!(mounts.map("/etc/hosts"), "/etc/hosts");
+        assert_eq!(mounts.unmap("/bits/src"), "/bits/src");
+    }
+
+    #[test]
+    fn matches_whole_components() {
+        let mounts = mapper(&[("/bits", "/Volumes/bits")]);
+
+        assert_eq!(mounts.map("/bitsy/src"), "/bitsy/src");
+        assert_eq!(mounts.unmap("/Volumes/bitsy/src"), "/Volumes/bitsy/src");
+    }
+
+    #[test]
+    fn prefers_the_longest_prefix() {
+        let mounts = mapper(&[
+            ("/bits", "/Volumes/bits"),
+            ("/bits/big", "/Volumes/big"),
+            ("/bits/big/deeper", "/Volumes/deeper"),
+        ]);
+
+        assert_eq!(mounts.map("/bits/big/file"), "/Volumes/big/file");
+        assert_eq!(mounts.map("/bits/big/deeper/file"), "/Volumes/deeper/file");
+        assert_eq!(mounts.map("/bits/small/file"), "/Volumes/bits/small/file");
+        assert_eq!(mounts.unmap("/Volumes/big/file"), "/bits/big/file");
+        assert_eq!(mounts.unmap("/Volumes/bits/big/file"), "/bits/big/file");
+    }
+}
@@ -0,0 +1,41 @@
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::messages;
+use crate::repo::Error;
+
+/// Ask which of `choices` to use, by number, on stderr so that `--print` output stays clean.
+///
+/// A single choice is taken without asking.
+pub fn pick(prompt: &str, choices: &[String]) -> Result<usize, Error> {
+    match choices.len() {
+        0 => return Err(Error::NotFound(messages::text("nothing-to-choose", &[]))),
+        1 => return Ok(0),
+        _ => {}
+    }
+
+    let mut stderr = io::stderr().lock();
+    let stdin_error = |err| Error::Io(PathBuf::from("<stdin>"), err);
+
+    for (number, choice) in choices.iter().enumerate() {
+        let _ = writeln!(stderr, "{:>3}) {choice}", number + 1);
+    }
+
+    let _ = write!(stderr, "{prompt} [1-{}]: ", choices.len());
+    let _ = stderr.flush();
+
+    let mut line = String::new();
+    io::stdin().read_line(&mut line).map_err(stdin_error)?;
+
+    line.trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|number| (1..=choices.len()).contains(number))
+        .map(|number| number - 1)
+        .ok_or_else(|| {
+            Error::NotFound(messages::text(
+                "no-such-choice",
+                &[("choice", &line.trim())],
+            ))
+        })
+}
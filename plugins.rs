@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::json::Value;
+use crate::watchdog;
+
+const PREFIX: &str = "magic-opener-resolve-";
+
+/// Ask each `magic-opener-resolve-*` executable on `$PATH`, in name order, to resolve `arg`.
+///
+/// A plugin reads the raw argument on stdin and, if it recognizes it, prints a JSON object with
+/// a `url` field, e.g. `{"url": "https://go/links/foo"}`. Anything else means "not mine".
+pub fn resolve(arg: &str) -> Option<String> {
+    plugins().into_values().find_map(|plugin| {
+        let output = watchdog::capture(&mut Command::new(plugin), Some(&format!("{arg}\n")))
+            .ok()?
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        Value::parse(&output.stdout)?
+            .get("url")?
+            .as_str()
+            .filter(|url| !url.is_empty())
+            .map(str::to_string)
+    })
+}
+
+/// Plugins by name. Like commands, the first on `$PATH` shadows any later ones.
+fn plugins() -> BTreeMap<String, PathBuf> {
+    let mut plugins = BTreeMap::new();
+
+    let Some(path) = env::var_os("PATH") else {
+        return plugins;
+    };
+
+    for dir in env::split_paths(&path) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(Result::ok) {
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if name.starts_with(PREFIX) && entry.path().is_file() {
+                plugins.entry(name).or_insert_with(|| entry.path());
+            }
+        }
+    }
+
+    plugins
+}
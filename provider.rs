@@ -0,0 +1,45 @@
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::messages;
+use crate::repo::HostKind;
+
+static OVERRIDE: OnceLock<Provider> = OnceLock::new();
+
+/// A URL layout forced with `--provider`, instead of the one detected from the host.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Provider {
+    Kind(HostKind),
+    /// `custom:<set>`: URLs from the `magic-opener.template.<set>.*` config templates.
+    Custom(String),
+}
+
+impl FromStr for Provider {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "github" => Ok(Self::Kind(HostKind::GitHub)),
+            "gitlab" => Ok(Self::Kind(HostKind::GitLab)),
+            "bitbucket" => Ok(Self::Kind(HostKind::Bitbucket)),
+            "gitea" => Ok(Self::Kind(HostKind::Gitea)),
+            "azure" => Ok(Self::Kind(HostKind::Azure)),
+            "codecommit" => Ok(Self::Kind(HostKind::CodeCommit)),
+            "sourcehut" => Ok(Self::Kind(HostKind::SourceHut)),
+            "gerrit" => Ok(Self::Kind(HostKind::Gerrit)),
+            _ => match value.strip_prefix("custom:") {
+                Some(set) if !set.is_empty() => Ok(Self::Custom(set.to_string())),
+                _ => Err(messages::text("bad-provider", &[("provider", &value)])),
+            },
+        }
+    }
+}
+
+/// Use `provider` for every repository for the rest of this run.
+pub fn set_override(provider: Provider) {
+    let _ = OVERRIDE.set(provider);
+}
+
+pub fn overridden() -> Option<&'static Provider> {
+    OVERRIDE.get()
+}
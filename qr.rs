@@ -0,0 +1,80 @@
+use std::env;
+use std::fs::File;
+use std::process::Command;
+
+use qrcode::{Color, EcLevel, QrCode};
+
+use crate::messages;
+use crate::repo::Error;
+
+/// Modules of margin around the code. The standard asks for four, but phones read codes with
+/// two, which keeps them on smaller terminals.
+const QUIET_ZONE: usize = 2;
+
+/// Columns to fit a code into when the terminal's width is unknown.
+const DEFAULT_COLUMNS: usize = 80;
+
+/// `text` as a QR code in Unicode half blocks, two modules to a character cell so it comes out
+/// square, with the most error correction that still fits the terminal's width.
+///
+/// Light modules are the ones drawn, as terminal text is usually light on dark.
+pub fn render(text: &str) -> Result<String, Error> {
+    let columns = columns().unwrap_or(DEFAULT_COLUMNS);
+
+    let code = [EcLevel::H, EcLevel::Q, EcLevel::M, EcLevel::L]
+        .into_iter()
+        .filter_map(|level| QrCode::with_error_correction_level(text, level).ok())
+        .find(|code| code.width() + 2 * QUIET_ZONE <= columns)
+        .ok_or_else(|| Error::NotFound(messages::text("qr-too-wide", &[("columns", &columns)])))?;
+
+    let width = code.width();
+    let colors = code.to_colors();
+    let size = width + 2 * QUIET_ZONE;
+
+    let light = |x: usize, y: usize| {
+        let (Some(x), Some(y)) = (x.checked_sub(QUIET_ZONE), y.checked_sub(QUIET_ZONE)) else {
+            return true;
+        };
+
+        x >= width || y >= width || colors[y * width + x] == Color::Light
+    };
+
+    let mut rendered = String::new();
+
+    for y in (0..size).step_by(2) {
+        for x in 0..size {
+            rendered.push(match (light(x, y), light(x, y + 1)) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            });
+        }
+
+        rendered.push('\n');
+    }
+
+    Ok(rendered)
+}
+
+/// The terminal's width, from `$COLUMNS` or else `stty`, which shells rarely export it for.
+fn columns() -> Option<usize> {
+    if let Some(columns) = env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+    {
+        return Some(columns);
+    }
+
+    let output = Command::new("stty")
+        .arg("size")
+        .stdin(File::open("/dev/tty").ok()?)
+        .output()
+        .ok()?;
+
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
@@ -0,0 +1,367 @@
+use std::path::Path;
+
+use crate::ci;
+use crate::location::Location;
+use crate::plugins;
+use crate::repo::{GitRepository, HostKind};
+use crate::shorthand::{self, Shorthand};
+use crate::trailers;
+
+/// What an argument turned out to name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Url,
+    File,
+    /// A file at a ref, from git's `<ref>:<path>` object syntax.
+    Blob,
+    Commit,
+    /// A `#123` on GitLab, where pull requests are `!123` instead, or an `org/name#123`.
+    Issue,
+    /// A `base..head` or `base...head` range, opened as a comparison.
+    Range,
+    Tag,
+    PullRequest,
+    Branch,
+    /// The CI run for a commit.
+    Ci,
+    /// An `org/name` on the default host.
+    Repository,
+    /// Claimed by a `magic-opener-resolve-*` plugin.
+    Plugin,
+}
+
+#[derive(Debug)]
+pub struct Resolution {
+    pub kind: Kind,
+    /// The URL to open, or for files, the argument as given.
+    pub target: String,
+}
+
+impl Kind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Url => "url",
+            Self::File => "file",
+            Self::Blob => "blob",
+            Self::Commit => "commit",
+            Self::Issue => "issue",
+            Self::Range => "range",
+            Self::Tag => "tag",
+            Self::PullRequest => "pull-request",
+            Self::Branch => "branch",
+            Self::Ci => "ci",
+            Self::Repository => "repository",
+            Self::Plugin => "plugin",
+        }
+    }
+}
+
+/// The kind of page `target` is, judged from its URL's path, for targets that weren't classified
+/// on the way, e.g. a subcommand's.
+pub fn kind_of(target: &str) -> Kind {
+    if !is_url(target) {
+        return Kind::File;
+    }
+
+    if let Some((kind, _)) = page_of(target) {
+        return kind;
+    }
+
+    if url_segments(target).len() == 2 {
+        Kind::Repository
+    } else {
+        Kind::Url
+    }
+}
+
+/// The kind of page a URL shows and which one, e.g. a pull request and its number, from the
+/// path segment marking it after the repository's own.
+pub fn page_of(target: &str) -> Option<(Kind, Option<&str>)> {
+    let segments = url_segments(target);
+
+    // Past the repository's own org and name.
+    segments
+        .iter()
+        .enumerate()
+        .skip(2)
+        .find_map(|(index, segment)| {
+            let kind = match *segment {
+                "blob" | "src" | "raw" => Kind::Blob,
+                "commit" | "commits" => Kind::Commit,
+                "compare" => Kind::Range,
+                "pull" | "pulls" | "pull-requests" | "merge_requests" => Kind::PullRequest,
+                "issues" => Kind::Issue,
+                "tree" => Kind::Branch,
+                "tags" | "releases" => Kind::Tag,
+                "actions" | "pipelines" | "checks" => Kind::Ci,
+                _ => return None,
+            };
+
+            Some((kind, segments.get(index + 1).copied()))
+        })
+}
+
+/// A URL's path segments, past its scheme and host.
+fn url_segments(url: &str) -> Vec<&str> {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+
+    path.split('/').skip(3).filter(|s| !s.is_empty()).collect()
+}
+
+/// Classify a single argument without opening anything.
+///
+/// Git is consulted for arguments that aren't files, to find a `<ref>:<path>`, a `base..head`
+/// range, a tag, a commit hash or revspec like `HEAD~3`, a `#123` / `!123` pull request reference
+/// or a branch, and plugins get a say before it's left to be treated as a path. An `org/name` or
+/// `org/name#123` needs no repository, naming one on the default host.
+pub fn resolve(arg: &str, cwd: &Path, remote: &str) -> Option<Resolution> {
+    classify(arg, cwd, remote, Mode::First).into_iter().next()
+}
+
+/// Each way to read `arg`, e.g. as a file and as a commit when a file is named like a hash, in
+/// the order [`resolve`] would prefer them.
+pub fn readings(arg: &str, cwd: &Path, remote: &str) -> Vec<Resolution> {
+    classify(arg, cwd, remote, Mode::Readings)
+}
+
+/// Everything an ambiguous argument could name, e.g. a file, a branch and a pull request all
+/// called `2024`, in the order [`resolve`] would prefer them. Without an argument, the pages of
+/// the current repository: itself, the branch's tree and the CI run for its commit.
+pub fn candidates(arg: &str, cwd: &Path, remote: &str) -> Vec<Resolution> {
+    if !arg.is_empty() {
+        return classify(arg, cwd, remote, Mode::All);
+    }
+
+    let Ok(repo) = GitRepository::from_path(cwd, remote) else {
+        return Vec::new();
+    };
+
+    let mut found = vec![Resolution {
+        kind: Kind::Repository,
+        target: repo.http_url(),
+    }];
+
+    if let Some(branch) = &repo.branch {
+        found.push(Resolution {
+            kind: Kind::Branch,
+            target: repo.tree_url(branch, ""),
+        });
+    }
+
+    if let Some(sha) = repo.resolve_commit("HEAD") {
+        found.push(Resolution {
+            kind: Kind::Ci,
+            target: ci::run_url(&repo, &sha),
+        });
+    }
+
+    found
+}
+
+/// How much [`classify`] looks for.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// The preferred reading only.
+    First,
+    /// Every reading, to choose from.
+    Readings,
+    /// Every reading, bare numbers as pull requests included, and the pages behind a commit, to
+    /// list. Bare numbers are too ambiguous to pick on their own.
+    All,
+}
+
+/// Classify `arg`, stopping at the first match in [`Mode::First`].
+fn classify(arg: &str, cwd: &Path, remote: &str, mode: Mode) -> Vec<Resolution> {
+    let mut found = Vec::new();
+
+    let mut add = |kind, target| {
+        found.push(Resolution { kind, target });
+        mode == Mode::First
+    };
+
+    if is_url(arg) {
+        add(Kind::Url, arg.to_string());
+        return found;
+    }
+
+    // Whether the argument names something here, a file, blob or branch, which a bare `org/name`
+    // isn't worth asking about alongside.
+    let mut local = Location::parse(arg).is_some();
+
+    if local && add(Kind::File, arg.to_string()) {
+        return found;
+    }
+
+    if let Some(url) = blob_url(arg, cwd, remote) {
+        local = true;
+
+        if add(Kind::Blob, url) {
+            return found;
+        }
+    }
+
+    let shorthand = Shorthand::parse(arg)
+        .filter(|shorthand| !shorthand.is_path_like(cwd))
+        .map(|shorthand| {
+            let repo = GitRepository::named(
+                cwd,
+                &shorthand::default_host(cwd),
+                &shorthand.org,
+                &shorthand.name,
+            );
+
+            match shorthand.number {
+                Some(number) if shorthand.pull_request => (Kind::PullRequest, repo.pr_url(number)),
+                Some(number) => (Kind::Issue, repo.issue_url(number)),
+                None => (Kind::Repository, repo.http_url()),
+            }
+        });
+
+    // A bare `org/name` could as well be a branch, which wins as the checkout's own.
+    if let Some((kind, url)) = shorthand
+        .clone()
+        .filter(|(kind, _)| *kind != Kind::Repository)
+    {
+        if add(kind, url) {
+            return found;
+        }
+    }
+
+    let number = arg
+        .strip_prefix('#')
+        .or_else(|| arg.strip_prefix('!'))
+        .or_else(|| (mode == Mode::All).then_some(arg))
+        .and_then(|number| number.parse::<u64>().ok());
+
+    if !arg.is_empty() && !arg.starts_with('-') {
+        if let Ok(repo) = GitRepository::from_path(cwd, remote) {
+            if let Some(url) = range_url(&repo, arg) {
+                if add(Kind::Range, url) {
+                    return found;
+                }
+            }
+
+            if repo.is_tag(arg) && add(Kind::Tag, repo.tree_url(arg, "")) {
+                return found;
+            }
+
+            if let Some(sha) = is_revspec(arg).then(|| repo.resolve_commit(arg)).flatten() {
+                if add(Kind::Commit, repo.commit_url(&sha)) {
+                    return found;
+                }
+
+                // Only a listing has room for the pull request and issues behind a commit.
+                if mode == Mode::All {
+                    if let Some(number) = repo.pr_for_commit(&sha) {
+                        add(Kind::PullRequest, repo.pr_url(number));
+                    }
+
+                    for url in trailers::closed_issues(&repo, &sha).unwrap_or_default() {
+                        add(Kind::Issue, url);
+                    }
+                }
+            }
+
+            if let Some(number) = number.filter(|_| arg.starts_with('#')) {
+                if repo.kind == HostKind::GitLab && add(Kind::Issue, repo.issue_url(number)) {
+                    return found;
+                }
+            }
+
+            if let Some(number) = number {
+                if add(Kind::PullRequest, repo.pr_url(number)) {
+                    return found;
+                }
+            }
+
+            if repo.is_branch(arg) {
+                local = true;
+
+                let repo = repo.pushed_to(arg).unwrap_or(repo);
+
+                if add(Kind::Branch, repo.tree_url(arg, "")) {
+                    return found;
+                }
+            }
+        }
+    }
+
+    if let Some((Kind::Repository, url)) = shorthand.filter(|_| !local) {
+        if add(Kind::Repository, url) {
+            return found;
+        }
+    }
+
+    if let Some(url) = plugins::resolve(arg) {
+        add(Kind::Plugin, url);
+    }
+
+    found
+}
+
+/// `<ref>:<path>[:line[-end]]`, with the path relative to the repository root as in git, once
+/// `git rev-parse` confirms the object exists.
+fn blob_url(arg: &str, cwd: &Path, remote: &str) -> Option<String> {
+    let (rev, rest) = arg.split_once(':')?;
+
+    if rev.is_empty() || rest.is_empty() {
+        return None;
+    }
+
+    let (path, lines) = match rest.rsplit_once(':') {
+        Some((path, lines)) if lines.chars().all(|c| c.is_ascii_digit() || c == '-') => {
+            (path, Some(lines))
+        }
+        _ => (rest, None),
+    };
+
+    let (start, end) = match lines.map(|lines| lines.split_once('-').unwrap_or((lines, ""))) {
+        Some((start, end)) => (Some(start.parse().ok()?), end.parse().ok()),
+        None => (None, None),
+    };
+
+    let repo = GitRepository::from_path(cwd, remote).ok()?;
+
+    repo.resolve_object(&format!("{rev}:{path}"))?;
+
+    Some(repo.blob_url(rev, path.trim_start_matches('/')) + &repo.line_anchor(start, end))
+}
+
+/// The comparison of a `base..head` or `base...head` range, where either end defaults to `HEAD`
+/// as in git.
+fn range_url(repo: &GitRepository, arg: &str) -> Option<String> {
+    let (base, head) = arg
+        .split_once("...")
+        .or_else(|| arg.split_once(".."))
+        .filter(|(base, head)| !(base.is_empty() && head.is_empty()))?;
+
+    let end = |rev: &str| {
+        let rev = if rev.is_empty() {
+            repo.head_ref()
+        } else {
+            rev.to_string()
+        };
+
+        repo.resolve_commit(&rev).map(|_| rev)
+    };
+
+    Some(repo.compare_url(&end(base)?, &end(head)?))
+}
+
+/// Whether `arg` is written like a commit rather than a name: a hash, a symbolic ref like `HEAD`
+/// or `ORIG_HEAD`, or anything using `~`, `^` or `@{...}`.
+fn is_revspec(arg: &str) -> bool {
+    is_sha(arg)
+        || arg == "@"
+        || arg.contains(['~', '^'])
+        || arg.contains("@{")
+        || (arg.ends_with("HEAD") && arg.chars().all(|c| c.is_ascii_uppercase() || c == '_'))
+}
+
+pub fn is_sha(arg: &str) -> bool {
+    (7..=40).contains(&arg.len()) && arg.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub fn is_url(target: &str) -> bool {
+    target.contains("://") || target.starts_with("mailto:")
+}
@@ -0,0 +1,29 @@
+# magic-opener: eval "$(@EXE@ init bash)"
+
+o() {
+  @EXE@ "$@"
+}
+
+# Open whatever is under the cursor: a URL, commit, pull request or file, doing nothing
+# unless it resolves.
+_magic_opener_word() {
+  local left="${READLINE_LINE:0:READLINE_POINT}"
+  local right="${READLINE_LINE:READLINE_POINT}"
+  local word="${left##* }${right%% *}" target
+
+  [[ -n $word ]] && target="$(@EXE@ --resolve-only "$word")" && @EXE@ "$target"
+}
+
+bind -x '"\C-o": _magic_opener_word'
+
+_magic_opener() {
+  local current="${COMP_WORDS[COMP_CWORD]}"
+
+  if [[ $COMP_CWORD -eq 1 ]]; then
+    mapfile -t COMPREPLY < <(compgen -W "@COMMANDS@" -- "$current"; compgen -f -- "$current")
+  else
+    mapfile -t COMPREPLY < <(compgen -f -- "$current")
+  fi
+}
+
+complete -o filenames -F _magic_opener o
@@ -0,0 +1,18 @@
+# magic-opener: @EXE@ init fish | source
+
+function o
+    @EXE@ $argv
+end
+
+# Open whatever is under the cursor: a URL, commit, pull request or file, doing nothing
+# unless it resolves.
+function __magic_opener_word
+    set -l word (commandline --current-token)
+
+    test -n "$word"; or return
+    set -l target (@EXE@ --resolve-only $word); and @EXE@ $target
+end
+
+bind \co __magic_opener_word
+
+complete -c o -n __fish_is_first_arg -a "@COMMANDS@"
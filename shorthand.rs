@@ -0,0 +1,121 @@
+use std::path::Path;
+
+use crate::config;
+
+const DEFAULT_HOST: &str = "github.com";
+
+/// Extensions that make a bare `org/name` a file's path rather than a repository, leaving out the
+/// few repository names end with too, like `next.js` and `socket.io`.
+const FILE_EXTENSIONS: [&str; 30] = [
+    "c", "cfg", "conf", "cpp", "css", "csv", "go", "h", "htm", "html", "ini", "java", "jpg",
+    "json", "lock", "log", "md", "pdf", "png", "py", "rs", "rst", "sh", "svg", "toml", "ts", "txt",
+    "xml", "yaml", "yml",
+];
+
+/// An `org/name` reference to a repository on the default host, or `org/name#123` to one of its
+/// issues or pull requests, as forges write them across repositories. With a number, the org may
+/// have subgroups, as on GitLab.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Shorthand {
+    pub org: String,
+    pub name: String,
+    pub number: Option<u64>,
+    /// Written `org/name!123`, GitLab's form for a merge request.
+    pub pull_request: bool,
+}
+
+impl Shorthand {
+    pub fn parse(arg: &str) -> Option<Self> {
+        let (path, number, pull_request) = if let Some((path, number)) = arg.rsplit_once('#') {
+            (path, Some(number), false)
+        } else if let Some((path, number)) = arg.rsplit_once('!') {
+            (path, Some(number), true)
+        } else {
+            (arg, None, false)
+        };
+
+        let (org, name) = path.rsplit_once('/')?;
+
+        let valid = |segment: &str| {
+            !segment.is_empty()
+                && !segment.starts_with('.')
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+        };
+
+        // A bare `a/b/c` is far more likely a mistyped path than a subgroup's repository.
+        if !org.split('/').all(valid) || !valid(name) || (number.is_none() && org.contains('/')) {
+            return None;
+        }
+
+        Some(Self {
+            org: org.to_string(),
+            name: name.to_string(),
+            number: number.map(str::parse).transpose().ok()?,
+            pull_request,
+        })
+    }
+}
+
+impl Shorthand {
+    /// Whether a bare `org/name` is more likely a path that's missing or mistyped, e.g.
+    /// `docs/readme.md`, or `src/mian` when there's a `src` directory in `cwd`.
+    pub fn is_path_like(&self, cwd: &Path) -> bool {
+        let extension = Path::new(&self.name).extension().is_some_and(|extension| {
+            FILE_EXTENSIONS.contains(&extension.to_string_lossy().to_lowercase().as_str())
+        });
+
+        self.number.is_none() && (extension || cwd.join(&self.org).is_dir())
+    }
+}
+
+/// The host shorthands refer to, from `magic-opener.default-host`.
+pub fn default_host(cwd: &Path) -> String {
+    config::get(cwd, "default-host").unwrap_or_else(|| DEFAULT_HOST.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::Shorthand;
+
+    #[test]
+    fn parses_references() {
+        let shorthand = Shorthand::parse("group/sub/name!12").expect("a shorthand");
+
+        assert_eq!(shorthand.org, "group/sub");
+        assert_eq!(shorthand.name, "name");
+        assert_eq!(shorthand.number, Some(12));
+        assert!(shorthand.pull_request);
+
+        for arg in ["a/b/c", "org/.hidden", "org/", "name", "org/name#x"] {
+            assert_eq!(Shorthand::parse(arg), None, "{arg}");
+        }
+    }
+
+    #[test]
+    fn tells_paths_from_repositories() {
+        let dir = env::temp_dir();
+
+        for (arg, path_like) in [
+            ("docs/readme.md", true),
+            ("build/index.HTML", true),
+            ("vercel/next.js", false),
+            ("socketio/socket.io", false),
+            ("dsully/magic-opener", false),
+            ("org/notes.md#3", false),
+        ] {
+            let shorthand = Shorthand::parse(arg).expect("a shorthand");
+
+            assert_eq!(shorthand.is_path_like(&dir), path_like, "{arg}");
+        }
+
+        let org = dir.file_name().expect("a name").to_string_lossy();
+        let parent = dir.parent().expect("a parent");
+        let shorthand = Shorthand::parse(&format!("{org}/name")).expect("a shorthand");
+
+        assert!(shorthand.is_path_like(parent));
+    }
+}
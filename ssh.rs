@@ -0,0 +1,59 @@
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+use crate::git;
+use crate::watchdog;
+
+/// Forges' own hosts, whose SSH config may point at an SSH-only endpoint, e.g. GitHub's
+/// `ssh.github.com` or GitLab's `altssh.gitlab.com` on port 443, that serves no web pages.
+const FORGES: [&str; 6] = [
+    "github.com",
+    "gitlab.com",
+    "bitbucket.org",
+    "codeberg.org",
+    "gitea.com",
+    "ssh.dev.azure.com",
+];
+
+/// Whether `host` may be an SSH alias, e.g. `work` or `github.com-work`, whose `HostName` is
+/// the host to browse, rather than a forge's own.
+pub fn is_alias(host: &str) -> bool {
+    !FORGES.contains(&host.to_lowercase().as_str())
+}
+
+/// The real hostname for an SSH remote's host, which may be an alias in `~/.ssh/config` or in
+/// whatever config `GIT_SSH_COMMAND` or `core.sshCommand` points `ssh` at.
+pub fn hostname(dir: &Path, host: &str) -> Option<String> {
+    let command = env::var("GIT_SSH_COMMAND")
+        .ok()
+        .or_else(|| git::output(dir, &["config", "--get", "core.sshCommand"]))
+        .unwrap_or_else(|| "ssh".to_string());
+
+    // Like git, let the shell split the command. `-G` only prints the resolved config.
+    let output = watchdog::capture(
+        Command::new("sh")
+            .arg("-c")
+            .arg(format!("{command} -G \"$1\""))
+            .arg("sh")
+            .arg(host),
+        None,
+    )
+    .ok()?
+    .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    output
+        .stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("hostname "))
+        .map(str::to_string)
+}
+
+/// Whether a remote URL is reached over SSH, either `ssh://` or scp-like `user@host:path`.
+pub fn is_ssh_url(url: &str) -> bool {
+    url.starts_with("ssh://") || url.starts_with("git+ssh://") || !url.contains("://")
+}
@@ -0,0 +1,83 @@
+use crate::git;
+use crate::messages;
+use crate::picker;
+use crate::repo::{Error, GitRepository};
+
+#[derive(Debug)]
+pub struct Submodule {
+    pub path: String,
+    pub url: String,
+}
+
+/// The submodules declared in `.gitmodules`, in file order.
+pub fn list(repo: &GitRepository) -> Vec<Submodule> {
+    let Some(output) = git::output(
+        &repo.root,
+        &[
+            "config",
+            "--file",
+            ".gitmodules",
+            "--get-regexp",
+            r"^submodule\..*\.(path|url)$",
+        ],
+    ) else {
+        return Vec::new();
+    };
+
+    // Each line is `submodule.<name>.<key> <value>`, and names may contain dots.
+    let mut entries: Vec<(String, Option<String>, Option<String>)> = Vec::new();
+
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(' ') else {
+            continue;
+        };
+
+        let Some((name, field)) = key
+            .strip_prefix("submodule.")
+            .and_then(|key| key.rsplit_once('.'))
+        else {
+            continue;
+        };
+
+        let index = entries
+            .iter()
+            .position(|(existing, ..)| existing == name)
+            .unwrap_or_else(|| {
+                entries.push((name.to_string(), None, None));
+                entries.len() - 1
+            });
+
+        match field {
+            "path" => entries[index].1 = Some(value.to_string()),
+            _ => entries[index].2 = Some(value.to_string()),
+        }
+    }
+
+    entries
+        .into_iter()
+        .filter_map(|(_, path, url)| {
+            Some(Submodule {
+                path: path?,
+                url: url?,
+            })
+        })
+        .collect()
+}
+
+/// Ask which submodule to open, and return its web page.
+pub fn pick_url(repo: &GitRepository) -> Result<String, Error> {
+    let submodules = list(repo);
+
+    if submodules.is_empty() {
+        return Err(Error::NotFound(messages::text("no-submodules", &[])));
+    }
+
+    let choices: Vec<String> = submodules
+        .iter()
+        .map(|submodule| format!("{}\t{}", submodule.path, submodule.url))
+        .collect();
+
+    repo.web_url_for(
+        &submodules[picker::pick(&messages::text("pick-submodule", &[]), &choices)?].url,
+    )
+}
@@ -0,0 +1,118 @@
+use crate::config;
+use crate::git;
+use crate::repo::{Error, GitRepository, HostKind};
+use crate::resolve::is_url;
+use crate::shorthand::Shorthand;
+
+/// Trailers whose `#123` values name issues in the same repository.
+const ISSUE_TRAILERS: [&str; 5] = ["fixes", "closes", "resolves", "refs", "see"];
+
+/// Words that close the issues after them when a commit lands, as GitHub and GitLab read them.
+const CLOSING_KEYWORDS: [&str; 9] = [
+    "close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved",
+];
+
+/// The commit message trailers of `rev`, as `(key, value)` pairs.
+fn trailers(repo: &GitRepository, rev: &str) -> Result<Vec<(String, String)>, Error> {
+    let output = git::run(
+        &repo.root,
+        &[
+            "show",
+            "--no-patch",
+            "--format=%(trailers:only,unfold)",
+            rev,
+        ],
+    )?;
+
+    Ok(output
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect())
+}
+
+/// The pages the trailers of `rev` point at: URLs as they are, `#123` issues of this repository,
+/// and values of any trailer with a `magic-opener.tracker.<trailer>` template, e.g.
+/// `https://jira.example.com/browse/{value}` for `Jira: ABC-456`.
+pub fn links(repo: &GitRepository, rev: &str) -> Result<Vec<String>, Error> {
+    let mut links: Vec<String> = trailers(repo, rev)?
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let key = key.to_lowercase();
+
+            if is_url(&value) {
+                return Some(value);
+            }
+
+            if let Some(template) = config::get(&repo.root, &format!("tracker.{key}")) {
+                return Some(template.replace("{value}", &value));
+            }
+
+            let number = value.strip_prefix('#')?.parse().ok()?;
+
+            ISSUE_TRAILERS
+                .contains(&key.as_str())
+                .then(|| repo.issue_url(number))
+        })
+        .collect();
+
+    for url in closed_issues(repo, rev)? {
+        if !links.contains(&url) {
+            links.push(url);
+        }
+    }
+
+    Ok(links)
+}
+
+/// The issues the message of `rev` closes, e.g. `Fixes #42` or `Closes org/name#7, #8`, where a
+/// reference to another repository means one on the same host. On GitLab, `!123` is a merge
+/// request rather than an issue.
+pub fn closed_issues(repo: &GitRepository, rev: &str) -> Result<Vec<String>, Error> {
+    let message = git::run(&repo.root, &["show", "--no-patch", "--format=%B", rev])?;
+
+    let mut urls: Vec<String> = Vec::new();
+    let mut closing = false;
+
+    for word in message.split_whitespace() {
+        let word = word.trim_end_matches([',', '.', ';', ')']);
+
+        if CLOSING_KEYWORDS.contains(&word.trim_end_matches(':').to_lowercase().as_str()) {
+            closing = true;
+            continue;
+        }
+
+        if !closing || word == "and" {
+            continue;
+        }
+
+        match reference_url(repo, word) {
+            Some(url) if !urls.contains(&url) => urls.push(url),
+            Some(_) => {}
+            None => closing = false,
+        }
+    }
+
+    Ok(urls)
+}
+
+/// The page of an `#123`, `!123` or `org/name#123` reference from `repo`.
+fn reference_url(repo: &GitRepository, reference: &str) -> Option<String> {
+    let (other, number, merge_request) = if let Some(shorthand) = Shorthand::parse(reference) {
+        let other = GitRepository::named(&repo.root, &repo.host, &shorthand.org, &shorthand.name);
+
+        (Some(other), shorthand.number?, shorthand.pull_request)
+    } else if let Some(number) = reference.strip_prefix('#') {
+        (None, number.parse().ok()?, false)
+    } else {
+        (None, reference.strip_prefix('!')?.parse().ok()?, true)
+    };
+
+    let target = other.as_ref().unwrap_or(repo);
+
+    match (merge_request, target.kind) {
+        (false, _) => Some(target.issue_url(number)),
+        (true, HostKind::GitLab) => Some(target.pr_url(number)),
+        (true, _) => None,
+    }
+}
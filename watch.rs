@@ -0,0 +1,84 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::glob;
+use crate::repo::Error;
+
+/// Poll `dir` every `interval` and hand each newly created file matching `pattern` to `found`.
+///
+/// Files already there when watching starts are left alone. A new file is only handed over once
+/// its size holds still for a poll, so a generator that's still writing it isn't raced. A
+/// pattern without a `/` matches file names at any depth, otherwise paths relative to `dir`.
+pub fn watch(
+    dir: &Path,
+    pattern: &str,
+    interval: Duration,
+    mut found: impl FnMut(&Path),
+) -> Result<(), Error> {
+    fs::read_dir(dir).map_err(|err| Error::Io(dir.to_path_buf(), err))?;
+
+    let mut seen: HashSet<PathBuf> = files(dir).into_keys().collect();
+    let mut pending: HashMap<PathBuf, u64> = HashMap::new();
+
+    loop {
+        thread::sleep(interval);
+
+        for (path, size) in files(dir) {
+            if seen.contains(&path) {
+                continue;
+            }
+
+            if pending.get(&path) == Some(&size) {
+                pending.remove(&path);
+
+                if matches(dir, pattern, &path) {
+                    found(&path);
+                }
+
+                seen.insert(path);
+            } else {
+                pending.insert(path, size);
+            }
+        }
+    }
+}
+
+fn matches(dir: &Path, pattern: &str, path: &Path) -> bool {
+    let relative = path.strip_prefix(dir).unwrap_or(path);
+
+    if pattern.contains('/') {
+        glob::matches(pattern, &relative.to_string_lossy())
+    } else {
+        relative
+            .file_name()
+            .is_some_and(|name| glob::matches(pattern, &name.to_string_lossy()))
+    }
+}
+
+/// Every file under `dir` with its size. Build directories come and go, so errors just leave
+/// entries out until the next poll.
+fn files(dir: &Path) -> HashMap<PathBuf, u64> {
+    let mut files = HashMap::new();
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(Result::ok) {
+            match entry.metadata() {
+                Ok(metadata) if metadata.is_dir() => dirs.push(entry.path()),
+                Ok(metadata) => {
+                    files.insert(entry.path(), metadata.len());
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    files
+}
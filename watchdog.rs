@@ -0,0 +1,110 @@
+use std::io::{self, Read, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How long a subprocess may run before it's presumed hung, in seconds. Zero waits forever.
+static TIMEOUT: AtomicU64 = AtomicU64::new(10);
+
+/// Set the watchdog for later commands, e.g. from the `magic-opener.timeout` setting.
+pub fn set_timeout(seconds: u64) {
+    TIMEOUT.store(seconds, Ordering::Relaxed);
+}
+
+pub struct Captured {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Run `command` with `input` on its stdin, capturing its output.
+///
+/// A command on a stale NFS or FUSE mount can block forever, so it's killed once it outlives
+/// the watchdog timeout, and `Err` carries the timeout in seconds.
+pub fn capture(command: &mut Command, input: Option<&str>) -> io::Result<Result<Captured, u64>> {
+    run(command, input, TIMEOUT.load(Ordering::Relaxed))
+}
+
+/// Like [`capture`], but waiting however long `command` takes, for transfers over the network
+/// that are slow rather than hung when they take a while, e.g. fetching a large pull request.
+pub fn capture_unwatched(command: &mut Command, input: Option<&str>) -> io::Result<Captured> {
+    run(command, input, 0)
+        .map(|captured| captured.unwrap_or_else(|_| unreachable!("no timeout to outlive")))
+}
+
+fn run(
+    command: &mut Command,
+    input: Option<&str>,
+    timeout: u64,
+) -> io::Result<Result<Captured, u64>> {
+    let mut child = command
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // A command that exits without reading its input isn't an error here.
+        let _ = stdin.write_all(input.as_bytes());
+    }
+
+    // Drain both pipes while waiting, so a chatty command can't fill one and stall.
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    Ok(wait(&mut child, timeout)?
+        .map(|status| Captured {
+            status,
+            stdout: collect(stdout),
+            stderr: collect(stderr),
+        })
+        .ok_or(timeout))
+}
+
+fn drain(pipe: Option<impl Read + Send + 'static>) -> Option<JoinHandle<Vec<u8>>> {
+    pipe.map(|mut pipe| {
+        thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = pipe.read_to_end(&mut buffer);
+            buffer
+        })
+    })
+}
+
+fn collect(handle: Option<JoinHandle<Vec<u8>>>) -> String {
+    let bytes = handle
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+
+    String::from_utf8_lossy(&bytes).trim_end().to_string()
+}
+
+/// Wait for `child` to exit, killing it and returning `None` after `timeout` seconds.
+fn wait(child: &mut Child, timeout: u64) -> io::Result<Option<ExitStatus>> {
+    if timeout == 0 {
+        return child.wait().map(Some);
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(timeout);
+    let mut interval = Duration::from_millis(1);
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+
+        thread::sleep(interval);
+        interval = (interval * 2).min(Duration::from_millis(50));
+    }
+}